@@ -0,0 +1,50 @@
+//! Compares reading raw blocks with and without a `Content-Length` header.
+#![feature(test)]
+
+extern crate ipfs_api as ipfs;
+extern crate test;
+extern crate url;
+
+use std::io::Cursor;
+
+use ipfs::transport::{Transport, Request, Response, Headers};
+use test::Bencher;
+use url::Url;
+
+const BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Serves a block for every request, optionally with its length.
+struct BlockTransport {
+    sized: bool,
+}
+
+impl Transport for BlockTransport {
+    fn send(&self, _: Request) -> ipfs::error::Result<Response> {
+        let mut headers = Headers::new();
+        if self.sized {
+            headers.set_raw("Content-Length", vec![BLOCK_SIZE.to_string().into_bytes()]);
+        }
+        Ok(Response {
+            status: 200,
+            headers: headers,
+            body: Box::new(Cursor::new(vec![0; BLOCK_SIZE])),
+        })
+    }
+}
+
+fn get_block(b: &mut Bencher, sized: bool) {
+    let client = ipfs::Client::with_transport(Url::parse("http://mock/api/v0/").unwrap(),
+                                              BlockTransport { sized: sized });
+    b.bytes = BLOCK_SIZE as u64;
+    b.iter(|| client.get_block("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap());
+}
+
+#[bench]
+fn get_block_sized(b: &mut Bencher) {
+    get_block(b, true);
+}
+
+#[bench]
+fn get_block_unsized(b: &mut Bencher) {
+    get_block(b, false);
+}
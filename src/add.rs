@@ -0,0 +1,341 @@
+//! API for adding files to IPFS.
+use std::io::{self, Read};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use api::{self, Client};
+use object::Reference;
+use block::HashFunction;
+use encoding::JsonStream;
+use transport::Upload;
+
+/// Options for [add_with](fn.add_with.html).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AddOptions {
+    /// Pin the added content (default: true).
+    pub pin: bool,
+    /// Store leaf data in raw blocks instead of wrapping it in UnixFS nodes
+    /// (default: false).
+    pub raw_leaves: bool,
+    /// Wrap the added file in a directory (default: false).
+    ///
+    /// The returned reference points to the directory. Use
+    /// [add_file](fn.add_file.html) to control the file's name within the
+    /// directory.
+    pub wrap_with_directory: bool,
+    /// The hash function to use (default: sha2-256).
+    pub hash: HashFunction,
+    /// The CID version to use (default: the IPFS node's default, currently 0).
+    ///
+    /// Version 0 CIDs only support sha2-256 so the IPFS node will switch to
+    /// version 1 CIDs when another hash function is specified.
+    pub cid_version: Option<u32>,
+}
+
+impl Default for AddOptions {
+    fn default() -> AddOptions {
+        AddOptions {
+            pin: true,
+            raw_leaves: false,
+            wrap_with_directory: false,
+            hash: HashFunction::Sha2_256,
+            cid_version: None,
+        }
+    }
+}
+
+/// Add a file to IPFS with the default options.
+///
+/// Unlike committing an [Object](../object/struct.Object.html), this chunks the
+/// data into a UnixFS file so the resulting hash matches the one produced by
+/// `ipfs add`.
+pub fn add(data: &[u8]) -> io::Result<Reference> {
+    api::default_client().add(data)
+}
+
+/// Add a file to IPFS with the specified options.
+pub fn add_with(data: &[u8], options: &AddOptions) -> io::Result<Reference> {
+    api::default_client().add_with(data, options)
+}
+
+/// Add the contents of a reader to IPFS with the default options.
+///
+/// The contents are streamed to the IPFS node rather than buffered in memory.
+/// As the reader can't be rewound, failed requests aren't retried (see
+/// [set_retry_policy](../fn.set_retry_policy.html)).
+pub fn add_reader<R: Read>(reader: R) -> io::Result<Reference> {
+    api::default_client().add_reader(reader)
+}
+
+/// Add the contents of a reader to IPFS with the specified options.
+///
+/// See [add_reader](fn.add_reader.html).
+pub fn add_reader_with<R: Read>(reader: R, options: &AddOptions) -> io::Result<Reference> {
+    api::default_client().add_reader_with(reader, options)
+}
+
+/// Add the contents of a reader to IPFS under the given filename.
+///
+/// The filename is only recorded if the file is wrapped in a directory (see
+/// [AddOptions::wrap_with_directory](struct.AddOptions.html#structfield.wrap_with_directory)).
+/// See [add_reader](fn.add_reader.html).
+pub fn add_file<R: Read>(filename: &str, reader: R, options: &AddOptions) -> io::Result<Reference> {
+    api::default_client().add_file(filename, reader, options)
+}
+
+/// Add the contents of a reader to IPFS, reporting progress as it goes.
+///
+/// The callback is called with the total number of bytes processed so far each
+/// time the IPFS node reports progress. See [add_reader](fn.add_reader.html).
+pub fn add_reader_with_progress<R, F>(reader: R, options: &AddOptions, progress: F) -> io::Result<Reference>
+    where R: Read,
+          F: FnMut(u64)
+{
+    api::default_client().add_reader_with_progress(reader, options, progress)
+}
+
+/// Add a file or directory (recursively) from the local filesystem to IPFS.
+///
+/// The top-level file or directory is always wrapped in a directory (whatever
+/// [AddOptions::wrap_with_directory](struct.AddOptions.html#structfield.wrap_with_directory)
+/// says) so that it keeps its name; the returned reference points to the
+/// wrapping directory. Empty directories are added as empty directories.
+/// Symbolic links aren't followed: they're skipped, and adding a path that is
+/// itself a symbolic link fails with an `io::ErrorKind::InvalidInput` error.
+///
+/// Files are streamed to the IPFS node one at a time so, like
+/// [add_reader](fn.add_reader.html), failed requests aren't retried.
+pub fn add_path(path: &Path, options: &AddOptions) -> io::Result<Reference> {
+    api::default_client().add_path(path, options)
+}
+
+/// Add a file or directory from the local filesystem to IPFS, returning the
+/// path (as reported by the IPFS node, e.g. `dir/sub/file.txt`) and a reference
+/// for every added file and directory.
+///
+/// The entries are returned in the order the IPFS node reports them (files
+/// before the directories containing them); the last entry is the wrapping
+/// directory (with an empty path) returned by [add_path](fn.add_path.html).
+pub fn add_path_all(path: &Path, options: &AddOptions) -> io::Result<Vec<(String, Reference)>> {
+    api::default_client().add_path_all(path, options)
+}
+
+// Opens the file on first read so we don't hold every file in the tree open at
+// once.
+struct LazyFile {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl Read for LazyFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.file.is_none() {
+            self.file = Some(File::open(&self.path)?);
+        }
+        self.file.as_mut().unwrap().read(buf)
+    }
+}
+
+// Collects the uploads for the file or directory at `path` (named `name`)
+// parents first, as expected by the IPFS node.
+fn collect_uploads(path: &Path, name: String, uploads: &mut Vec<Upload<'static>>) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        return Ok(());
+    }
+    if !metadata.is_dir() {
+        uploads.push(Upload {
+            data: Box::new(LazyFile {
+                path: path.to_owned(),
+                file: None,
+            }),
+            filename: Some(name),
+            mime: None,
+        });
+        return Ok(());
+    }
+
+    uploads.push(Upload {
+        data: Box::new(io::empty()),
+        filename: Some(name.clone()),
+        mime: Some("application/x-directory".parse().unwrap()),
+    });
+    let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    for entry in entries {
+        let child = match entry.file_name().into_string() {
+            Ok(child) => child,
+            Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidInput, "file names must be valid UTF-8")),
+        };
+        collect_uploads(&entry.path(), format!("{}/{}", name, child), uploads)?;
+    }
+    Ok(())
+}
+
+struct AddResult {
+    name: String,
+    hash: String,
+    size: String,
+}
+
+// With `progress=true`, the IPFS node interleaves progress records (with only a
+// `Bytes` field) with the usual results.
+#[derive(Deserialize)]
+struct AddRecord {
+    #[serde(rename="Name", default)]
+    name: String,
+    #[serde(rename="Bytes")]
+    bytes: Option<u64>,
+    #[serde(rename="Hash")]
+    hash: Option<String>,
+    #[serde(rename="Size")]
+    size: Option<String>,
+}
+
+// Reads the results streamed back by `add`, passing progress records to
+// `progress`.
+fn read_results<F: FnMut(u64)>(resp: Box<Read + Send>, mut progress: F) -> io::Result<Vec<AddResult>> {
+    let mut results = Vec::new();
+    for record in JsonStream::<AddRecord>::new(resp) {
+        match record? {
+            AddRecord { name, hash: Some(hash), size: Some(size), .. } => {
+                results.push(AddResult {
+                    name: name,
+                    hash: hash,
+                    size: size,
+                })
+            }
+            AddRecord { bytes: Some(bytes), .. } => progress(bytes),
+            _ => (),
+        }
+    }
+    Ok(results)
+}
+
+// When wrapping, the IPFS node returns one result per file followed by one for
+// the directory.
+fn into_reference(client: &Client, results: Vec<AddResult>) -> io::Result<Reference> {
+    let result = match results.into_iter().last() {
+        Some(result) => result,
+        None => return Err(io::Error::new(io::ErrorKind::InvalidData, "empty add response")),
+    };
+    let size = result.size.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(Reference::new(result.hash, size).with_client(client))
+}
+
+fn add_args<'a>(options: &AddOptions, cid_version: &'a Option<String>) -> Vec<(&'static str, &'a str)> {
+    let mut args = vec![
+        ("pin", api::bool_to_str(options.pin)),
+        ("raw-leaves", api::bool_to_str(options.raw_leaves)),
+        ("wrap-with-directory", api::bool_to_str(options.wrap_with_directory)),
+        ("hash", options.hash.as_str()),
+    ];
+    if let Some(ref v) = *cid_version {
+        args.push(("cid-version", &v[..]));
+    }
+    args
+}
+
+impl Client {
+    /// Add a file to IPFS. See [add](fn.add.html).
+    pub fn add(&self, data: &[u8]) -> io::Result<Reference> {
+        self.add_with(data, &AddOptions::default())
+    }
+
+    /// Add a file to IPFS with the specified options. See
+    /// [add_with](add/fn.add_with.html).
+    pub fn add_with(&self, data: &[u8], options: &AddOptions) -> io::Result<Reference> {
+        let cid_version = options.cid_version.map(|v| v.to_string());
+        let resp = api::post_data_raw(self, "add", &add_args(options, &cid_version), data)?;
+        into_reference(self, read_results(resp, |_| ())?)
+    }
+
+    /// Add the contents of a reader to IPFS. See [add_reader](fn.add_reader.html).
+    pub fn add_reader<R: Read>(&self, reader: R) -> io::Result<Reference> {
+        self.add_reader_with(reader, &AddOptions::default())
+    }
+
+    /// Add the contents of a reader to IPFS with the specified options. See
+    /// [add_reader_with](add/fn.add_reader_with.html).
+    pub fn add_reader_with<R: Read>(&self, mut reader: R, options: &AddOptions) -> io::Result<Reference> {
+        let cid_version = options.cid_version.map(|v| v.to_string());
+        let upload = Upload {
+            data: Box::new(&mut reader),
+            filename: None,
+            mime: None,
+        };
+        let resp = api::post_uploads_raw(self, "add", &add_args(options, &cid_version), vec![upload])?;
+        into_reference(self, read_results(resp, |_| ())?)
+    }
+
+    /// Add the contents of a reader to IPFS under the given filename. See
+    /// [add_file](add/fn.add_file.html).
+    pub fn add_file<R: Read>(&self, filename: &str, mut reader: R, options: &AddOptions) -> io::Result<Reference> {
+        let cid_version = options.cid_version.map(|v| v.to_string());
+        let upload = Upload {
+            data: Box::new(&mut reader),
+            filename: Some(filename.to_owned()),
+            mime: None,
+        };
+        let resp = api::post_uploads_raw(self, "add", &add_args(options, &cid_version), vec![upload])?;
+        into_reference(self, read_results(resp, |_| ())?)
+    }
+
+    /// Add the contents of a reader to IPFS, reporting progress. See
+    /// [add_reader_with_progress](add/fn.add_reader_with_progress.html).
+    pub fn add_reader_with_progress<R, F>(&self, mut reader: R, options: &AddOptions, progress: F) -> io::Result<Reference>
+        where R: Read,
+              F: FnMut(u64)
+    {
+        let cid_version = options.cid_version.map(|v| v.to_string());
+        let mut args = add_args(options, &cid_version);
+        args.push(("progress", "true"));
+        let upload = Upload {
+            data: Box::new(&mut reader),
+            filename: None,
+            mime: None,
+        };
+        let resp = api::post_uploads_raw(self, "add", &args, vec![upload])?;
+        into_reference(self, read_results(resp, progress)?)
+    }
+
+    /// Add a file or directory from the local filesystem to IPFS. See
+    /// [add_path](add/fn.add_path.html).
+    pub fn add_path(&self, path: &Path, options: &AddOptions) -> io::Result<Reference> {
+        into_reference(self, self.add_path_results(path, options)?)
+    }
+
+    /// Add a file or directory from the local filesystem to IPFS, returning
+    /// every added entry. See [add_path_all](add/fn.add_path_all.html).
+    pub fn add_path_all(&self, path: &Path, options: &AddOptions) -> io::Result<Vec<(String, Reference)>> {
+        self.add_path_results(path, options)?
+            .into_iter()
+            .map(|result| {
+                let size = result.size.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                Ok((result.name, Reference::new(result.hash, size).with_client(self)))
+            })
+            .collect()
+    }
+
+    fn add_path_results(&self, path: &Path, options: &AddOptions) -> io::Result<Vec<AddResult>> {
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name.to_owned(),
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid path")),
+        };
+        let mut uploads = Vec::new();
+        collect_uploads(path, name, &mut uploads)?;
+        if uploads.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "can't add a symbolic link"));
+        }
+
+        let options = AddOptions {
+            wrap_with_directory: true,
+            ..*options
+        };
+        let cid_version = options.cid_version.map(|v| v.to_string());
+        let mut args = add_args(&options, &cid_version);
+        args.push(("recursive", "true"));
+        let resp = api::post_uploads_raw(self, "add", &args, uploads)?;
+        read_results(resp, |_| ())
+    }
+}
@@ -1,23 +1,286 @@
-use std::sync::RwLock;
 use std::io::{self, Read};
+use std::path::Path;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use multipart::client::Multipart;
 use url::{self, Url, UrlParser};
-use hyper::{self, net};
+use hyper::net::{NetworkConnector, NetworkStream};
 use hyper::client::pool::Pool;
 use hyper::method::Method;
-use hyper::client::request::Request;
+use hyper::mime::Mime;
+use hyper::header::{Headers, Authorization, Basic, Bearer, Connection, ContentLength, UserAgent};
 
 use encoding::{Json, Encoding};
+use error::{self, Error, Result};
+use transport::{Transport, Request, Response, Upload, PoolConfig};
+use object::Reference;
+use path::{IpfsPath, Namespace};
+use version::VersionNumber;
+#[cfg(unix)]
+use unix::UnixConnector;
 
 const API_VERSION: &'static str = "v0";
 
-thread_local! {
-    static CONN_POOL: Pool<net::DefaultConnector> = Pool::new(Default::default())
+// The most lookups a client caches; the least recently used are dropped first.
+const MAX_CACHED_LOOKUPS: usize = 1024;
+
+/// A handle to an IPFS API endpoint.
+///
+/// Clones share the same connection pool. The free functions in this crate use
+/// a default client configured with [set_api_endpoint](fn.set_api_endpoint.html),
+/// [set_connector](fn.set_connector.html), and
+/// [set_timeout](fn.set_timeout.html).
+#[derive(Clone)]
+pub struct Client {
+    base: Url,
+    transport: Arc<Transport>,
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    auth: Option<Auth>,
+    keep_alive_uploads: bool,
+    user_agent: String,
+    gateway_fallback: Option<Url>,
+    resolve_ttl: Option<Duration>,
+    resolve_cache: Arc<Mutex<ResolveCache>>,
+    // The IPFS node's version number, once known (see `node_version`).
+    node_version: Arc<Mutex<Option<VersionNumber>>>,
+}
+
+// Lookup results keyed by path and whether the lookup was offline.
+#[derive(Default)]
+struct ResolveCache {
+    entries: HashMap<(String, bool), CachedReference>,
+    // Incremented on every use, to find the least recently used entry.
+    clock: u64,
+}
+
+// A cached lookup result, when it expires (`None` for `/ipfs/` paths) and when
+// it was last used.
+#[derive(Clone)]
+struct CachedReference {
+    reference: Reference,
+    expires: Option<Instant>,
+    used: u64,
+}
+
+/// A policy for retrying requests that fail with transient errors.
+///
+/// Requests are retried when the IPFS node can't be reached. Requests that
+/// don't change anything (`GET`s) are also retried when the connection is
+/// reset, when they time out, when the node reports an internal error
+/// (`ERR_IMPLEMENTATION`), and when a proxy in front of the node reports it as
+/// unavailable (502, 503, or 504). Errors such as an invalid path or an object
+/// that isn't pinned are never retried.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts (including the first).
+    pub max_attempts: u32,
+    /// The delay before the first retry. This is doubled after every retry.
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Credentials sent with every request (in the `Authorization` header).
+///
+/// Use this to talk to IPFS nodes behind an authenticating reverse proxy.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Auth {
+    /// HTTP basic authentication.
+    Basic {
+        /// The username.
+        username: String,
+        /// The password, if any.
+        password: Option<String>,
+    },
+    /// A bearer token.
+    Bearer(String),
+}
+
+/// The `User-Agent` sent with requests unless configured otherwise.
+pub const DEFAULT_USER_AGENT: &'static str = concat!("rust-ipfs-api/", env!("CARGO_PKG_VERSION"));
+
+impl Client {
+    fn from_transport(base: Url, transport: Arc<Transport>) -> Client {
+        Client {
+            base: base,
+            transport: transport,
+            timeout: None,
+            retry: None,
+            auth: None,
+            keep_alive_uploads: false,
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            gateway_fallback: None,
+            resolve_ttl: None,
+            resolve_cache: Arc::new(Mutex::new(ResolveCache::default())),
+            node_version: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Create a client for the IPFS API at the given URL (e.g.
+    /// `http://127.0.0.1:5001/api/v0/`).
+    ///
+    /// The `/api/v0/` path is appended to the URL if it's missing (so
+    /// `http://127.0.0.1:5001` works too).
+    pub fn new(base: Url) -> Client {
+        Client::from_transport(normalize_base(base), Arc::new(Pool::new(Default::default())))
+    }
+
+    /// Create a client for the IPFS API at the given URL with a custom
+    /// connection pool configuration.
+    ///
+    /// The pool keeps up to `max_idle` idle connections per host open for
+    /// reuse (default: 5).
+    pub fn with_pool_config(base: Url, config: PoolConfig) -> Client {
+        Client::from_transport(normalize_base(base), Arc::new(Pool::new(config)))
+    }
+
+    /// Create a client for the IPFS API at the given URL using a custom
+    /// connector.
+    ///
+    /// Use this to connect to an `https://` endpoint with a custom TLS
+    /// configuration. Requests to an endpoint whose scheme isn't supported by
+    /// the connector fail with an error.
+    pub fn with_connector<C, S>(base: Url, connector: C) -> Client
+        where C: NetworkConnector<Stream=S> + Send + Sync + 'static,
+              S: NetworkStream + Send
+    {
+        Client::from_transport(normalize_base(base), Arc::new(Pool::with_connector(Default::default(), connector)))
+    }
+
+    /// Create a client for the IPFS API at the given URL using a custom
+    /// transport. See [transport](transport/index.html).
+    pub fn with_transport<T>(base: Url, transport: T) -> Client
+        where T: Transport + 'static
+    {
+        Client::from_transport(normalize_base(base), Arc::new(transport))
+    }
+
+    /// Create a client for the IPFS API listening on the unix socket at the
+    /// given path.
+    ///
+    /// Fails on platforms without unix sockets.
+    pub fn with_unix_socket<P: AsRef<Path>>(path: P) -> io::Result<Client> {
+        Ok(Client::from_transport(unix_socket_url(), unix_socket_transport(path.as_ref())?))
+    }
+
+    /// Get the IPFS API endpoint.
+    pub fn endpoint(&self) -> &Url {
+        &self.base
+    }
+
+    /// Set the read/write timeout for API requests (default: none).
+    ///
+    /// Requests that time out fail with an `io::ErrorKind::TimedOut` error.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Get the read/write timeout for API requests.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Set the policy for retrying failed requests (default: none).
+    pub fn set_retry_policy(&mut self, retry: Option<RetryPolicy>) {
+        self.retry = retry;
+    }
+
+    /// Get the policy for retrying failed requests.
+    pub fn retry_policy(&self) -> Option<RetryPolicy> {
+        self.retry
+    }
+
+    /// Set the credentials sent with every request (default: none).
+    pub fn set_auth(&mut self, auth: Option<Auth>) {
+        self.auth = auth;
+    }
+
+    /// Get the credentials sent with every request.
+    pub fn auth(&self) -> Option<&Auth> {
+        self.auth.as_ref()
+    }
+
+    /// Cache the results of lookups (e.g. [object::lookup](object/fn.lookup.html)),
+    /// keeping the results for `/ipns/` paths for the given time (default:
+    /// none, i.e. don't cache).
+    ///
+    /// `/ipfs/` paths are immutable so their results are kept until the cache
+    /// is cleared or, once it holds 1024 results, until they're the least
+    /// recently used. Offline and online lookups are cached separately. Clones
+    /// of this client share the same cache.
+    pub fn set_resolve_cache_ttl(&mut self, ttl: Option<Duration>) {
+        self.resolve_ttl = ttl;
+    }
+
+    /// Get how long lookups of `/ipns/` paths are cached for.
+    pub fn resolve_cache_ttl(&self) -> Option<Duration> {
+        self.resolve_ttl
+    }
+
+    /// Forget all cached lookups.
+    pub fn clear_resolve_cache(&self) {
+        self.resolve_cache.lock().unwrap().entries.clear();
+    }
+
+    /// Reuse connections after uploading files (default: false).
+    ///
+    /// By default, requests that upload files ask the IPFS node to close the
+    /// connection afterwards as some versions of the node reply before reading
+    /// the entire upload, leaving the connection unusable.
+    pub fn set_keep_alive_uploads(&mut self, keep_alive: bool) {
+        self.keep_alive_uploads = keep_alive;
+    }
+
+    /// Check whether connections are reused after uploading files.
+    pub fn keep_alive_uploads(&self) -> bool {
+        self.keep_alive_uploads
+    }
+
+    /// Set the `User-Agent` sent with every request (default:
+    /// [DEFAULT_USER_AGENT](constant.DEFAULT_USER_AGENT.html)).
+    pub fn set_user_agent<S: Into<String>>(&mut self, user_agent: S) {
+        self.user_agent = user_agent.into();
+    }
+
+    /// Get the `User-Agent` sent with every request.
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Read content through the given (read-only) HTTP gateway (e.g.
+    /// `https://ipfs.io/`) when the IPFS node can't be reached (default:
+    /// none).
+    ///
+    /// Only [cat](cat/fn.cat.html), [cat_range](cat/fn.cat_range.html) and
+    /// [object::get](object/fn.get.html) (of `/ipfs/<hash>` paths) fall back to
+    /// the gateway and only when connecting to the IPFS node fails; errors
+    /// reported by the node (e.g. not found) are returned as-is.
+    ///
+    /// Objects read through the gateway are checked against their hash, but
+    /// file contents read with `cat` are **not verified**: they're streamed
+    /// from the gateway as-is, so only use a gateway you trust.
+    pub fn set_gateway_fallback(&mut self, gateway: Option<Url>) {
+        self.gateway_fallback = gateway;
+    }
+
+    /// Get the gateway used when the IPFS node can't be reached.
+    pub fn gateway_fallback(&self) -> Option<&Url> {
+        self.gateway_fallback.as_ref()
+    }
 }
 
 lazy_static! {
-    static ref IPFS_BASE: RwLock<Url> = RwLock::new(Url {
+    static ref DEFAULT_CLIENT: RwLock<Client> = RwLock::new(Client::new(Url {
         scheme: String::from("http"),
         scheme_data: url::SchemeData::Relative(url::RelativeSchemeData {
             host: url::Host::Domain(String::from("127.0.0.1")),
@@ -29,9 +292,13 @@ lazy_static! {
         }),
         query: None,
         fragment: None,
-    });
+    }));
 }
 
+/// Get (a handle to) the default client.
+pub fn default_client() -> Client {
+    DEFAULT_CLIENT.read().unwrap().clone()
+}
 
 #[derive(Debug, Deserialize)]
 struct IpfsError {
@@ -43,19 +310,217 @@ struct IpfsError {
 
 pub mod ipfs_error {
     pub const NOT_PINNED: &'static str = "not pinned";
+    pub const ALREADY_PINNED: &'static str = "already pinned";
     pub const INVALID_REF: &'static str = "invalid ipfs ref path";
+    pub const NOT_FOUND: &'static str = "merkledag: not found";
+    pub const DEADLINE_EXCEEDED: &'static str = "context deadline exceeded";
+}
+
+// The IPFS node reports pinning conflicts with the generic error code and
+// their wording changes between versions (e.g. "not pinned" vs. "not pinned or
+// pinned indirectly"), so check the code and look for the message.
+fn is_pin_error(e: &Error, msg: &str) -> bool {
+    match *e {
+        Error::Api { code, ref message } => code == error::ERR_NORMAL && message.contains(msg),
+        _ => false,
+    }
+}
+
+/// Returns true if the IPFS node reported that an object isn't pinned.
+pub fn is_not_pinned(e: &Error) -> bool {
+    is_pin_error(e, ipfs_error::NOT_PINNED)
+}
+
+/// Returns true if the IPFS node reported that an object is already pinned.
+pub fn is_already_pinned(e: &Error) -> bool {
+    is_pin_error(e, ipfs_error::ALREADY_PINNED)
+}
+
+/// Talk to the IPFS API over the unix socket at the given path.
+///
+/// Fails on platforms without unix sockets.
+pub fn set_unix_socket<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let transport = unix_socket_transport(path.as_ref())?;
+    let mut client = DEFAULT_CLIENT.write().unwrap();
+    client.base = unix_socket_url();
+    client.transport = transport;
+    client.node_version = Arc::new(Mutex::new(None));
+    Ok(())
+}
+
+// The host is ignored when connecting over a unix socket.
+fn unix_socket_url() -> Url {
+    Url::parse(&format!("http://unix/api/{}/", API_VERSION)).unwrap()
+}
+
+#[cfg(unix)]
+fn unix_socket_transport(path: &Path) -> io::Result<Arc<Transport>> {
+    Ok(Arc::new(Pool::with_connector(Default::default(), UnixConnector(path.to_owned()))))
+}
+
+#[cfg(not(unix))]
+fn unix_socket_transport(_: &Path) -> io::Result<Arc<Transport>> {
+    Err(io::Error::new(io::ErrorKind::Other, "unix sockets aren't supported on this platform"))
 }
 
 /// Set the IPFS API endpoint
+///
+/// The `/api/v0/` path is appended to the URL if it's missing.
 pub fn set_api_endpoint(url: Url) {
-    *IPFS_BASE.write().unwrap() = url;
+    let mut client = DEFAULT_CLIENT.write().unwrap();
+    client.base = normalize_base(url);
+    client.node_version = Arc::new(Mutex::new(None));
+}
+
+// API methods are resolved relative to the base URL so it must end in
+// `/api/v0/` (including the trailing slash).
+fn normalize_base(mut url: Url) -> Url {
+    if let url::SchemeData::Relative(ref mut data) = url.scheme_data {
+        if data.path.last().map_or(false, |s| s.is_empty()) {
+            data.path.pop();
+        }
+        let n = data.path.len();
+        if n < 2 || data.path[n - 2] != "api" || data.path[n - 1] != API_VERSION {
+            data.path.push(String::from("api"));
+            data.path.push(String::from(API_VERSION));
+        }
+        data.path.push(String::new());
+    }
+    url
 }
 
 /// Get the IPFS API endpoint
 pub fn get_api_endpoint() -> Url {
-    IPFS_BASE.read().unwrap().clone()
+    DEFAULT_CLIENT.read().unwrap().base.clone()
+}
+
+/// Set the connector used to talk to the IPFS API.
+///
+/// Use this to connect to an `https://` endpoint with a custom TLS
+/// configuration. Requests to an endpoint whose scheme isn't supported by the
+/// connector fail with an error.
+pub fn set_connector<C, S>(connector: C)
+    where C: NetworkConnector<Stream=S> + Send + Sync + 'static,
+          S: NetworkStream + Send
+{
+    DEFAULT_CLIENT.write().unwrap().transport = Arc::new(Pool::with_connector(Default::default(), connector));
 }
 
+/// Set the read/write timeout for API requests (default: none).
+///
+/// Requests that time out fail with an `io::ErrorKind::TimedOut` error.
+pub fn set_timeout(timeout: Option<Duration>) {
+    DEFAULT_CLIENT.write().unwrap().timeout = timeout;
+}
+
+/// Get the read/write timeout for API requests.
+pub fn get_timeout() -> Option<Duration> {
+    DEFAULT_CLIENT.read().unwrap().timeout
+}
+
+/// Set the policy for retrying failed requests (default: none).
+pub fn set_retry_policy(retry: Option<RetryPolicy>) {
+    DEFAULT_CLIENT.write().unwrap().retry = retry;
+}
+
+/// Set the credentials sent with every request (default: none).
+pub fn set_auth(auth: Option<Auth>) {
+    DEFAULT_CLIENT.write().unwrap().auth = auth;
+}
+
+/// Cache the results of lookups (default: none). See
+/// [Client::set_resolve_cache_ttl](struct.Client.html#method.set_resolve_cache_ttl).
+pub fn set_resolve_cache_ttl(ttl: Option<Duration>) {
+    DEFAULT_CLIENT.write().unwrap().resolve_ttl = ttl;
+}
+
+/// Forget all cached lookups.
+pub fn clear_resolve_cache() {
+    DEFAULT_CLIENT.read().unwrap().clear_resolve_cache();
+}
+
+/// Reuse connections after uploading files (default: false). See
+/// [Client::set_keep_alive_uploads](struct.Client.html#method.set_keep_alive_uploads).
+pub fn set_keep_alive_uploads(keep_alive: bool) {
+    DEFAULT_CLIENT.write().unwrap().keep_alive_uploads = keep_alive;
+}
+
+/// Set the `User-Agent` sent with every request (default:
+/// [DEFAULT_USER_AGENT](constant.DEFAULT_USER_AGENT.html)).
+pub fn set_user_agent<S: Into<String>>(user_agent: S) {
+    DEFAULT_CLIENT.write().unwrap().user_agent = user_agent.into();
+}
+
+/// Read content through the given HTTP gateway when the IPFS node can't be
+/// reached (default: none). File contents read from the gateway aren't
+/// verified. See
+/// [Client::set_gateway_fallback](struct.Client.html#method.set_gateway_fallback).
+pub fn set_gateway_fallback(gateway: Option<Url>) {
+    DEFAULT_CLIENT.write().unwrap().gateway_fallback = gateway;
+}
+
+/// Get the IPFS node's version number, only asking the node the first time
+/// (per client and its clones).
+pub fn node_version(client: &Client) -> io::Result<VersionNumber> {
+    if let Some(version) = *client.node_version.lock().unwrap() {
+        return Ok(version);
+    }
+    let version = client.version()?.version;
+    *client.node_version.lock().unwrap() = Some(version);
+    Ok(version)
+}
+
+/// Look up a path in the client's lookup cache.
+pub fn cached_lookup(client: &Client, path: &IpfsPath, offline: bool) -> Option<Reference> {
+    if client.resolve_ttl.is_none() {
+        return None;
+    }
+    let mut cache = client.resolve_cache.lock().unwrap();
+    let key = (path.as_str().to_owned(), offline);
+    cache.clock += 1;
+    let clock = cache.clock;
+    let fresh = match cache.entries.get_mut(&key) {
+        Some(cached) => {
+            cached.used = clock;
+            cached.expires.map_or(true, |expires| expires > Instant::now())
+        }
+        None => return None,
+    };
+    if fresh {
+        cache.entries.get(&key).map(|c| c.reference.clone().with_client(client))
+    } else {
+        cache.entries.remove(&key);
+        None
+    }
+}
+
+/// Add a lookup result to the client's lookup cache (if enabled).
+pub fn cache_lookup(client: &Client, path: &IpfsPath, offline: bool, reference: &Reference) {
+    let ttl = match client.resolve_ttl {
+        Some(ttl) => ttl,
+        None => return,
+    };
+    let expires = match path.namespace() {
+        Namespace::Ipfs => None,
+        Namespace::Ipns => Some(Instant::now() + ttl),
+    };
+    let key = (path.as_str().to_owned(), offline);
+    let mut cache = client.resolve_cache.lock().unwrap();
+    if cache.entries.len() >= MAX_CACHED_LOOKUPS && !cache.entries.contains_key(&key) {
+        let lru = cache.entries.iter().min_by_key(|&(_, c)| c.used).map(|(k, _)| k.clone());
+        if let Some(lru) = lru {
+            cache.entries.remove(&lru);
+        }
+    }
+    cache.clock += 1;
+    let clock = cache.clock;
+    cache.entries.insert(key, CachedReference {
+        // Don't keep the client (and so the cache itself) alive from the cache.
+        reference: Reference::new(reference.hash().to_owned(), reference.size()),
+        expires: expires,
+        used: clock,
+    });
+}
 
 /// Helper.
 pub fn bool_to_str(b: bool) -> &'static str {
@@ -66,72 +531,308 @@ pub fn bool_to_str(b: bool) -> &'static str {
     }
 }
 
-fn request(method: Method, url: Url) -> hyper::Result<Request<net::Fresh>> {
-    CONN_POOL.with(|pool| Request::with_connector(method, url, pool))
+/// Helper.
+pub fn duration_to_str(d: Duration) -> String {
+    format!("{}s{}ns", d.as_secs(), d.subsec_nanos())
 }
 
-// Panics if method is not a valid URL path.
-fn make_url(method: &str, args: &[(&str, &str)], encoding: Option<&str>) -> Url {
-    let mut url = match UrlParser::new().base_url(&IPFS_BASE.read().unwrap()).parse(method) {
-        Ok(v) => v,
-        Err(_) => panic!("invalid url"),
+// `idempotent` is false for requests that may have had an effect even though
+// they failed (e.g. adding a pin).
+fn is_transient(e: &Error, idempotent: bool) -> bool {
+    match *e {
+        // The request never reached the node.
+        Error::Io(ref e) if e.kind() == io::ErrorKind::ConnectionRefused => true,
+        _ if !idempotent => false,
+        Error::Io(ref e) => {
+            match e.kind() {
+                io::ErrorKind::ConnectionReset | io::ErrorKind::TimedOut => true,
+                _ => false,
+            }
+        }
+        Error::Api { code, .. } => code == error::ERR_IMPLEMENTATION,
+        // Proxies report an unreachable or overloaded IPFS node this way.
+        Error::Status { status, .. } => status == 502 || status == 503 || status == 504,
+        _ => false,
+    }
+}
+
+fn with_retry<T, F>(client: &Client, idempotent: bool, mut f: F) -> Result<T>
+    where F: FnMut() -> Result<T>
+{
+    let policy = match client.retry {
+        Some(policy) => policy,
+        None => return f(),
     };
+    let mut backoff = policy.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(ref e) if attempt < policy.max_attempts && is_transient(e, idempotent) => {
+                thread::sleep(backoff);
+                backoff = backoff * 2;
+                attempt += 1;
+            }
+            r => return r,
+        }
+    }
+}
+
+fn send(client: &Client, method: Method, url: Url, uploads: Vec<Upload>) -> Result<Response> {
+    let mut headers = Headers::new();
+    headers.set(UserAgent(client.user_agent.clone()));
+    match client.auth {
+        Some(Auth::Basic { ref username, ref password }) => {
+            headers.set(Authorization(Basic {
+                username: username.clone(),
+                password: password.clone(),
+            }));
+        }
+        Some(Auth::Bearer(ref token)) => headers.set(Authorization(Bearer { token: token.clone() })),
+        None => (),
+    }
+    if !uploads.is_empty() && !client.keep_alive_uploads {
+        headers.set(Connection::close());
+    }
+    client.transport.send(Request {
+        method: method,
+        url: url,
+        headers: headers,
+        timeout: client.timeout,
+        uploads: uploads,
+    })
+}
+
+// Resolves a path (an API method or a gateway path) relative to the base URL.
+fn join_url(client: &Client, path: &str) -> Result<Url> {
+    UrlParser::new().base_url(&client.base).parse(path).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("invalid url path {:?}: {}", path, e)).into()
+    })
+}
+
+// Argument values are passed through as-is: `set_query_from_pairs`
+// form-urlencodes them (spaces, `#`, `&`, `+`, `%`, non-ASCII), which is what
+// the IPFS node decodes.
+fn make_url(client: &Client, method: &str, args: &[(&str, &str)], encoding: Option<&str>) -> Result<Url> {
+    let mut url = join_url(client, method)?;
     url.set_query_from_pairs(encoding.map(|e|("encoding", e)).iter().chain(args));
-    url
+    Ok(url)
+}
+
+// How much of a non-IPFS error body to keep in the error.
+const MAX_ERROR_SNIPPET: usize = 256;
+
+// How much of an error body to read. IPFS errors are small; anything bigger is
+// a (truncated) page from something else.
+const MAX_ERROR_BODY: u64 = 64 * 1024;
+
+fn check_status(mut response: Response) -> Result<Response> {
+    if response.status >= 200 && response.status < 300 {
+        return Ok(response);
+    }
+    let mut body = Vec::new();
+    (&mut response.body).take(MAX_ERROR_BODY).read_to_end(&mut body)?;
+    match Json::parse(&mut &body[..], None) {
+        Ok(IpfsError { code, message }) => Err(Error::Api {
+            code: code,
+            message: message,
+        }),
+        // Not from the IPFS node (e.g. an HTML error page from a proxy).
+        Err(_) => {
+            let body = String::from_utf8_lossy(&body);
+            let body = body.trim();
+            let snippet = match body.char_indices().nth(MAX_ERROR_SNIPPET) {
+                Some((end, _)) => format!("{}...", &body[..end]),
+                None => body.to_owned(),
+            };
+            Err(Error::Status {
+                status: response.status,
+                body: snippet,
+            })
+        }
+    }
 }
 
-fn handle_error<P, T>(mut response: hyper::client::Response) -> io::Result<T>
+fn handle_error<P, T>(response: Response) -> Result<T>
     where P: Encoding<T>
 {
-    if response.status.is_success() {
-        P::parse(&mut response)
-    } else {
-        let result: IpfsError = Json::parse(&mut response)?;
-        return Err(io::Error::new(io::ErrorKind::Other, result.message))
+    let mut response = check_status(response)?;
+    let len = response.headers.get::<ContentLength>().map(|&ContentLength(len)| len);
+    P::parse(&mut response.body, len)
+}
+
+/// Make a GET request, returning the raw response body for streaming.
+pub fn get_raw(client: &Client, method: &str, args: &[(&str, &str)]) -> Result<Box<Read + Send>> {
+    with_retry(client, true, || {
+        let resp = send(client, Method::Get, make_url(client, method, args, None)?, vec![])?;
+        Ok(check_status(resp)?.body)
+    })
+}
+
+/// Make a POST request, returning the raw response body for streaming.
+pub fn post_raw(client: &Client, method: &str, args: &[(&str, &str)]) -> Result<Box<Read + Send>> {
+    with_retry(client, false, || {
+        let resp = send(client, Method::Post, make_url(client, method, args, None)?, vec![])?;
+        Ok(check_status(resp)?.body)
+    })
+}
+
+/// Fetch an `/ipfs/` or `/ipns/` path from the gateway served alongside the
+/// API.
+pub fn get_gateway(client: &Client, path: &str) -> Result<Response> {
+    let url = join_url(client, path)?;
+    check_gateway_status(with_retry(client, true, || send(client, Method::Get, url.clone(), vec![]))?)
+}
+
+/// Returns true if the error means that the IPFS node couldn't be reached at
+/// all (as opposed to the request failing).
+pub fn is_connection_error(e: &io::Error) -> bool {
+    match e.kind() {
+        io::ErrorKind::ConnectionRefused |
+        io::ErrorKind::ConnectionAborted |
+        io::ErrorKind::NotConnected |
+        io::ErrorKind::AddrNotAvailable => true,
+        _ => false,
     }
 }
 
-pub fn get<P, T>(method: &str, args: &[(&str, &str)]) -> io::Result<T>
+/// Fetch an `/ipfs/` path from the client's fallback gateway, if any.
+pub fn get_fallback(client: &Client, path: &str) -> Option<Result<Response>> {
+    client.gateway_fallback.as_ref().map(|gateway| {
+        let url = UrlParser::new().base_url(gateway).parse(path).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid url path {:?}: {}", path, e))
+        })?;
+        // Don't leak the API credentials to a third party.
+        let mut headers = Headers::new();
+        headers.set(UserAgent(client.user_agent.clone()));
+        check_gateway_status(client.transport.send(Request {
+            method: Method::Get,
+            url: url,
+            headers: headers,
+            timeout: client.timeout,
+            uploads: vec![],
+        })?)
+    })
+}
+
+fn check_gateway_status(mut resp: Response) -> Result<Response> {
+    if resp.status >= 200 && resp.status < 300 {
+        return Ok(resp);
+    }
+    // The gateway returns plain text errors.
+    let mut message = String::new();
+    resp.body.read_to_string(&mut message)?;
+    let kind = if resp.status == 404 {
+        io::ErrorKind::NotFound
+    } else {
+        io::ErrorKind::Other
+    };
+    Err(io::Error::new(kind, message.trim()).into())
+}
+
+pub fn get<P, T>(client: &Client, method: &str, args: &[(&str, &str)]) -> Result<T>
     where P: Encoding<T>
 {
-    let resp = match request(Method::Get, make_url(method, args, <P as Encoding<T>>::ENCODING)).and_then(|r| r.start()).and_then(|r| r.send()) {
-        Ok(v) => v,
-        Err(hyper::Error::Io(e)) => return Err(e),
-        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
-    };
-    handle_error::<P, T>(resp)
+    with_retry(client, true, || {
+        let resp = send(client, Method::Get, make_url(client, method, args, <P as Encoding<T>>::ENCODING)?, vec![])?;
+        handle_error::<P, T>(resp)
+    })
 }
 
-pub fn post<P, T>(method: &str, args: &[(&str, &str)]) -> io::Result<T> 
+pub fn post<P, T>(client: &Client, method: &str, args: &[(&str, &str)]) -> Result<T>
     where P: Encoding<T>
-    {
-    let resp = match request(Method::Post, make_url(method, args, <P as Encoding<T>>::ENCODING)).and_then(|r| r.start()).and_then(|r| r.send()) {
-        Ok(v) => v,
-        Err(hyper::Error::Io(e)) => return Err(e),
-        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
-    };
-    handle_error::<P, T>(resp)
+{
+    with_retry(client, false, || {
+        let resp = send(client, Method::Post, make_url(client, method, args, <P as Encoding<T>>::ENCODING)?, vec![])?;
+        handle_error::<P, T>(resp)
+    })
 }
 
-pub fn post_data<P, T>(method: &str,
+pub fn post_data<P, T>(client: &Client,
+            method: &str,
             args: &[(&str, &str)],
             data: &[u8])
-            -> io::Result<T>
+            -> Result<T>
     where P: Encoding<T>
-    {
-    let resp = match request(Method::Post, make_url(method, args, <P as Encoding<T>>::ENCODING))
-                         .and_then(|mut r| {
-                             r.headers_mut().set(hyper::header::Connection::close());
-                             Multipart::from_request(r)
-                         })
-                         .and_then(|mut r| {
-                             // XXX: Why does rust insist that this must be used?
-                             let _ = r.write_stream("data", &mut &*data, None, None);
-                             r.send()
-                         }) {
-        Ok(v) => v,
-        Err(hyper::Error::Io(e)) => return Err(e),
-        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+{
+    post_file::<P, T>(client, method, args, data, None, None)
+}
+
+/// Like `post_data` but also sends a filename and/or content type with the
+/// data.
+pub fn post_file<P, T>(client: &Client,
+                       method: &str,
+                       args: &[(&str, &str)],
+                       data: &[u8],
+                       filename: Option<&str>,
+                       mime: Option<Mime>)
+                       -> Result<T>
+    where P: Encoding<T>
+{
+    with_retry(client, false, || {
+        post_reader::<P, T, _>(client, method, args, &mut &*data, filename, mime.clone())
+    })
+}
+
+/// Stream the request body from a reader.
+///
+/// Unlike `post_data`, this never retries as the reader can't be rewound.
+pub fn post_reader<P, T, R>(client: &Client,
+                            method: &str,
+                            args: &[(&str, &str)],
+                            data: &mut R,
+                            filename: Option<&str>,
+                            mime: Option<Mime>)
+                            -> Result<T>
+    where P: Encoding<T>,
+          R: Read
+{
+    let upload = Upload {
+        data: Box::new(data),
+        filename: filename.map(str::to_owned),
+        mime: mime,
     };
+    post_uploads::<P, T>(client, method, args, vec![upload])
+}
+
+/// Upload several files in one request.
+///
+/// Like `post_reader`, this never retries.
+pub fn post_uploads<P, T>(client: &Client,
+                          method: &str,
+                          args: &[(&str, &str)],
+                          uploads: Vec<Upload>)
+                          -> Result<T>
+    where P: Encoding<T>
+{
+    let resp = send(client, Method::Post, make_url(client, method, args, <P as Encoding<T>>::ENCODING)?, uploads)?;
     handle_error::<P, T>(resp)
 }
+
+/// Like `post_data`, returning the raw response body for streaming.
+pub fn post_data_raw(client: &Client,
+                     method: &str,
+                     args: &[(&str, &str)],
+                     data: &[u8])
+                     -> Result<Box<Read + Send>> {
+    with_retry(client, false, || {
+        let upload = Upload {
+            data: Box::new(data),
+            filename: None,
+            mime: None,
+        };
+        post_uploads_raw(client, method, args, vec![upload])
+    })
+}
+
+/// Upload several files in one request, returning the raw response body for
+/// streaming.
+///
+/// Like `post_reader`, this never retries.
+pub fn post_uploads_raw(client: &Client,
+                        method: &str,
+                        args: &[(&str, &str)],
+                        uploads: Vec<Upload>)
+                        -> Result<Box<Read + Send>> {
+    let resp = send(client, Method::Post, make_url(client, method, args, Some("json"))?, uploads)?;
+    Ok(check_status(resp)?.body)
+}
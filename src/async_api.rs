@@ -0,0 +1,46 @@
+//! Future-returning variants of the core calls.
+//!
+//! The IPFS API client is blocking so these run the blocking calls on a shared
+//! thread pool and return a future for the result. The sync and async calls
+//! can be freely mixed: both go through the same client and connection pool.
+use std::io;
+
+use futures_cpupool::{CpuPool, CpuFuture};
+
+use api::Client;
+use object::{CommittedObject, Reference, Stat, StatError};
+
+lazy_static! {
+    static ref POOL: CpuPool = CpuPool::new_num_cpus();
+}
+
+impl Client {
+    /// Get an object without blocking. See [object::get](object/fn.get.html).
+    pub fn get_async(&self, path: &str) -> CpuFuture<CommittedObject, io::Error> {
+        let client = self.clone();
+        let path = path.to_owned();
+        POOL.spawn_fn(move || client.get(&path))
+    }
+
+    /// Lookup information about an object without blocking. See
+    /// [object::stat](object/fn.stat.html).
+    pub fn stat_async(&self, path: &str) -> CpuFuture<Stat, StatError> {
+        let client = self.clone();
+        let path = path.to_owned();
+        POOL.spawn_fn(move || client.stat(&path))
+    }
+
+    /// Resolve an IPFS path without blocking. See
+    /// [name::resolve](name/fn.resolve.html).
+    pub fn resolve_async(&self, path: &str, recursive: bool) -> CpuFuture<String, io::Error> {
+        let client = self.clone();
+        let path = path.to_owned();
+        POOL.spawn_fn(move || client.resolve(&path, recursive))
+    }
+
+    /// Add a file to IPFS without blocking. See [add](fn.add.html).
+    pub fn add_async(&self, data: Vec<u8>) -> CpuFuture<Reference, io::Error> {
+        let client = self.clone();
+        POOL.spawn_fn(move || client.add(&data))
+    }
+}
@@ -0,0 +1,92 @@
+//! API for inspecting the node's block exchange (bitswap).
+use std::io;
+
+use api::{self, Client};
+use encoding::Json;
+
+/// Statistics about the node's block exchange.
+///
+/// Returned from [bitswap_stat](fn.bitswap_stat.html).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BitswapStat {
+    /// The CIDs of the blocks the node is waiting on.
+    pub wantlist: Vec<String>,
+    /// The IDs of the peers the node is exchanging blocks with.
+    pub peers: Vec<String>,
+    /// The number of blocks received.
+    pub blocks_received: u64,
+    /// The number of bytes received.
+    pub data_received: u64,
+    /// The number of duplicate blocks received.
+    pub dup_blks_received: u64,
+    #[doc(hidden)]
+    _non_exhaustive: (),
+}
+
+#[derive(Deserialize)]
+struct CidLink {
+    #[serde(rename="/")]
+    cid: String,
+}
+
+fn cids(links: Option<Vec<CidLink>>) -> Vec<String> {
+    links.unwrap_or_else(Vec::new).into_iter().map(|l| l.cid).collect()
+}
+
+/// Get statistics about the node's block exchange.
+pub fn bitswap_stat() -> io::Result<BitswapStat> {
+    api::default_client().bitswap_stat()
+}
+
+/// List the CIDs of the blocks the node (or, if specified, the given peer) is
+/// waiting on.
+pub fn wantlist(peer: Option<&str>) -> io::Result<Vec<String>> {
+    api::default_client().bitswap_wantlist(peer)
+}
+
+impl Client {
+    /// Get statistics about the node's block exchange. See
+    /// [bitswap::bitswap_stat](bitswap/fn.bitswap_stat.html).
+    pub fn bitswap_stat(&self) -> io::Result<BitswapStat> {
+        #[derive(Deserialize)]
+        struct StatResult {
+            #[serde(rename="Wantlist", default)]
+            wantlist: Option<Vec<CidLink>>,
+            #[serde(rename="Peers", default)]
+            peers: Option<Vec<String>>,
+            #[serde(rename="BlocksReceived", default)]
+            blocks_received: u64,
+            #[serde(rename="DataReceived", default)]
+            data_received: u64,
+            #[serde(rename="DupBlksReceived", default)]
+            dup_blks_received: u64,
+        }
+
+        let resp = api::get::<Json, StatResult>(self, "bitswap/stat", &[])?;
+        Ok(BitswapStat {
+            wantlist: cids(resp.wantlist),
+            peers: resp.peers.unwrap_or_else(Vec::new),
+            blocks_received: resp.blocks_received,
+            data_received: resp.data_received,
+            dup_blks_received: resp.dup_blks_received,
+            _non_exhaustive: (),
+        })
+    }
+
+    /// List the CIDs of the blocks a node is waiting on. See
+    /// [bitswap::wantlist](bitswap/fn.wantlist.html).
+    pub fn bitswap_wantlist(&self, peer: Option<&str>) -> io::Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct WantlistResult {
+            #[serde(rename="Keys", default)]
+            keys: Option<Vec<CidLink>>,
+        }
+
+        let mut args = vec![];
+        if let Some(peer) = peer {
+            args.push(("peer", peer));
+        }
+        let resp = api::get::<Json, WantlistResult>(self, "bitswap/wantlist", &args)?;
+        Ok(cids(resp.keys))
+    }
+}
@@ -0,0 +1,145 @@
+//! API for working with raw blocks.
+use std::io;
+
+use api::{self, Client};
+use object::Reference;
+use encoding::{Json, Raw};
+
+/// The format of a block.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlockFormat {
+    /// A merkledag (protobuf) node, addressed by a v0 CID.
+    Protobuf,
+    /// Raw bytes.
+    Raw,
+    /// A CBOR encoded IPLD node.
+    Cbor,
+}
+
+impl BlockFormat {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            BlockFormat::Protobuf => "v0",
+            BlockFormat::Raw => "raw",
+            BlockFormat::Cbor => "cbor",
+        }
+    }
+}
+
+/// A hash function supported by IPFS.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HashFunction {
+    /// SHA2-256 (the default).
+    Sha2_256,
+    /// SHA2-512.
+    Sha2_512,
+    /// SHA3-512.
+    Sha3_512,
+    /// BLAKE2b-256.
+    Blake2b256,
+    /// BLAKE2b-512.
+    Blake2b512,
+}
+
+impl Default for HashFunction {
+    fn default() -> HashFunction {
+        HashFunction::Sha2_256
+    }
+}
+
+impl HashFunction {
+    /// The name of the hash function as understood by IPFS.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            HashFunction::Sha2_256 => "sha2-256",
+            HashFunction::Sha2_512 => "sha2-512",
+            HashFunction::Sha3_512 => "sha3-512",
+            HashFunction::Blake2b256 => "blake2b-256",
+            HashFunction::Blake2b512 => "blake2b-512",
+        }
+    }
+}
+
+/// Options for [put_block_with](fn.put_block_with.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct PutBlockOptions {
+    /// The hash function to use (default: sha2-256).
+    pub hash: HashFunction,
+    /// The CID version to use (default: the IPFS node's default).
+    ///
+    /// `Protobuf` blocks are addressed by version 0 CIDs (which only support
+    /// sha2-256) unless this is 1. Blocks in other formats always get version 1
+    /// CIDs.
+    pub cid_version: Option<u32>,
+}
+
+/// Information about a block.
+///
+/// Returned from [stat_block](fn.stat_block.html).
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct BlockStat {
+    /// The block's CID.
+    #[serde(rename="Key")]
+    pub key: String,
+    /// The block's size in bytes.
+    #[serde(rename="Size")]
+    pub size: u64,
+}
+
+/// Get the raw contents of a block.
+pub fn get_block(cid: &str) -> io::Result<Vec<u8>> {
+    api::default_client().get_block(cid)
+}
+
+/// Store a block.
+///
+/// The returned reference's size is the size of this block (it doesn't include
+/// the size of any linked blocks).
+pub fn put_block(data: &[u8], format: BlockFormat) -> io::Result<Reference> {
+    api::default_client().put_block(data, format)
+}
+
+/// Store a block with the specified options (e.g. the hash function). See
+/// [put_block](fn.put_block.html).
+pub fn put_block_with(data: &[u8], format: BlockFormat, options: &PutBlockOptions) -> io::Result<Reference> {
+    api::default_client().put_block_with(data, format, options)
+}
+
+/// Get information about a block.
+pub fn stat_block(cid: &str) -> io::Result<BlockStat> {
+    api::default_client().stat_block(cid)
+}
+
+impl Client {
+    /// Get the raw contents of a block. See [block::get_block](block/fn.get_block.html).
+    pub fn get_block(&self, cid: &str) -> io::Result<Vec<u8>> {
+        Ok(api::get::<Raw, Vec<u8>>(self, "block/get", &[("arg", cid)])?)
+    }
+
+    /// Store a block. See [block::put_block](block/fn.put_block.html).
+    pub fn put_block(&self, data: &[u8], format: BlockFormat) -> io::Result<Reference> {
+        self.put_block_with(data, format, &PutBlockOptions::default())
+    }
+
+    /// Store a block with the specified options. See
+    /// [block::put_block_with](block/fn.put_block_with.html).
+    pub fn put_block_with(&self, data: &[u8], format: BlockFormat, options: &PutBlockOptions) -> io::Result<Reference> {
+        let format = match (format, options.cid_version) {
+            // "v0" asks for a version 0 CID.
+            (BlockFormat::Protobuf, Some(1)) => "protobuf",
+            (format, _) => format.as_str(),
+        };
+        let cid_version = options.cid_version.map(|v| v.to_string());
+        let mut args = vec![("format", format), ("mhtype", options.hash.as_str())];
+        if let Some(ref v) = cid_version {
+            args.push(("cid-version", &v[..]));
+        }
+        let stat = api::post_data::<Json, BlockStat>(self, "block/put", &args, data)?;
+        Ok(Reference::new(stat.key, stat.size).with_client(self))
+    }
+
+    /// Get information about a block. See [block::stat_block](block/fn.stat_block.html).
+    pub fn stat_block(&self, cid: &str) -> io::Result<BlockStat> {
+        Ok(api::get::<Json, BlockStat>(self, "block/stat", &[("arg", cid)])?)
+    }
+}
@@ -0,0 +1,71 @@
+//! API for managing the IPFS node's bootstrap peers.
+//!
+//! The node connects to its bootstrap peers on startup. Private networks should
+//! replace the default list with their own peers.
+use std::io;
+
+use api::{self, Client};
+use encoding::Json;
+
+#[derive(Deserialize)]
+struct PeersResult {
+    #[serde(rename="Peers", default)]
+    peers: Option<Vec<String>>,
+}
+
+/// List the node's bootstrap peers (as multiaddrs).
+pub fn bootstrap_list() -> io::Result<Vec<String>> {
+    api::default_client().bootstrap_list()
+}
+
+/// Add a bootstrap peer (a multiaddr ending in `/ipfs/<peer ID>`).
+///
+/// Returns the added peers.
+pub fn bootstrap_add(multiaddr: &str) -> io::Result<Vec<String>> {
+    api::default_client().bootstrap_add(multiaddr)
+}
+
+/// Add the default bootstrap peers.
+///
+/// Returns the added peers.
+pub fn bootstrap_add_default() -> io::Result<Vec<String>> {
+    api::default_client().bootstrap_add_default()
+}
+
+/// Remove a bootstrap peer.
+///
+/// Returns the removed peers.
+pub fn bootstrap_rm(multiaddr: &str) -> io::Result<Vec<String>> {
+    api::default_client().bootstrap_rm(multiaddr)
+}
+
+impl Client {
+    fn bootstrap(&self, method: &str, args: &[(&str, &str)]) -> io::Result<Vec<String>> {
+        let resp = api::post::<Json, PeersResult>(self, method, args)?;
+        Ok(resp.peers.unwrap_or_else(Vec::new))
+    }
+
+    /// List the bootstrap peers. See
+    /// [bootstrap::bootstrap_list](bootstrap/fn.bootstrap_list.html).
+    pub fn bootstrap_list(&self) -> io::Result<Vec<String>> {
+        self.bootstrap("bootstrap/list", &[])
+    }
+
+    /// Add a bootstrap peer. See
+    /// [bootstrap::bootstrap_add](bootstrap/fn.bootstrap_add.html).
+    pub fn bootstrap_add(&self, multiaddr: &str) -> io::Result<Vec<String>> {
+        self.bootstrap("bootstrap/add", &[("arg", multiaddr)])
+    }
+
+    /// Add the default bootstrap peers. See
+    /// [bootstrap::bootstrap_add_default](bootstrap/fn.bootstrap_add_default.html).
+    pub fn bootstrap_add_default(&self) -> io::Result<Vec<String>> {
+        self.bootstrap("bootstrap/add", &[("default", "true")])
+    }
+
+    /// Remove a bootstrap peer. See
+    /// [bootstrap::bootstrap_rm](bootstrap/fn.bootstrap_rm.html).
+    pub fn bootstrap_rm(&self, multiaddr: &str) -> io::Result<Vec<String>> {
+        self.bootstrap("bootstrap/rm", &[("arg", multiaddr)])
+    }
+}
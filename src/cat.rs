@@ -0,0 +1,227 @@
+//! API for reading the raw contents of UnixFS files.
+use std::cmp;
+use std::io::{self, Read, Seek, SeekFrom};
+
+
+use hyper::header::ContentType;
+use hyper::mime::Mime;
+
+use api::{self, Client};
+use error::Error;
+
+/// A streaming reader over the contents of a file.
+///
+/// Returned from [cat](fn.cat.html).
+pub struct CatReader {
+    response: Box<Read + Send>,
+    // Bytes left to read (if limited). Not all versions of go-ipfs respect the
+    // length parameter.
+    remaining: Option<u64>,
+}
+
+impl Read for CatReader {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = match self.remaining {
+            Some(remaining) => cmp::min(buf.len() as u64, remaining) as usize,
+            None => buf.len(),
+        };
+        if len == 0 {
+            return Ok(0);
+        }
+        let n = self.response.read(&mut buf[..len]).map_err(|e| io::Error::from(Error::from(e)))?;
+        if let Some(ref mut remaining) = self.remaining {
+            *remaining -= n as u64;
+        }
+        Ok(n)
+    }
+}
+
+/// Read the contents of the file at the given path.
+///
+/// IPNS names are resolved first. The file is streamed from the IPFS node
+/// rather than being buffered into memory.
+pub fn cat(path: &str) -> io::Result<CatReader> {
+    api::default_client().cat(path)
+}
+
+/// Read `length` bytes (or the rest of the file if `None`) of the file at the
+/// given path, starting at `offset`.
+///
+/// Reading past the end of the file yields no data. See [cat](fn.cat.html).
+pub fn cat_range(path: &str, offset: u64, length: Option<u64>) -> io::Result<CatReader> {
+    api::default_client().cat_range(path, offset, length)
+}
+
+/// Open the file at the given path for random access.
+///
+/// IPNS names are resolved once, when the file is opened. See
+/// [UnixFsFile](struct.UnixFsFile.html).
+pub fn open_file(path: &str) -> io::Result<UnixFsFile> {
+    api::default_client().open_file(path)
+}
+
+/// A UnixFS file supporting random access.
+///
+/// Returned from [open_file](fn.open_file.html). Data is streamed from the
+/// IPFS node starting at the current position; seeking to a different position
+/// starts a new request on the next read (so prefer large sequential reads to
+/// many small seeks).
+pub struct UnixFsFile {
+    client: Client,
+    path: String,
+    len: u64,
+    pos: u64,
+    reader: Option<CatReader>,
+}
+
+impl UnixFsFile {
+    /// The size of the file.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Check whether the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The resolved (`/ipfs/`) path of the file.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Read for UnixFsFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.reader.is_none() {
+            self.reader = Some(self.client.cat_resolved(&self.path, self.pos, None)?);
+        }
+        let n = self.reader.as_mut().unwrap().read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for UnixFsFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => seek_offset(self.pos, offset),
+            SeekFrom::End(offset) => seek_offset(self.len, offset),
+        };
+        let pos = match pos {
+            Some(pos) => pos,
+            None => {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "invalid seek to a negative or overflowing position"));
+            }
+        };
+        if pos != self.pos {
+            self.pos = pos;
+            self.reader = None;
+        }
+        Ok(self.pos)
+    }
+}
+
+// Offsets a position, returning `None` if the result is negative or overflows.
+fn seek_offset(base: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        base.checked_add(offset as u64)
+    } else {
+        // -i64::MIN doesn't fit in an i64.
+        base.checked_sub((-(offset + 1)) as u64 + 1)
+    }
+}
+
+/// Read the file at the given path through the IPFS node's gateway, returning
+/// the file's content type (as sniffed by the gateway) along with its contents.
+///
+/// The path may be a hash or an `/ipfs/` or `/ipns/` path.
+pub fn fetch_with_type(path: &str) -> io::Result<(CatReader, Option<Mime>)> {
+    api::default_client().fetch_with_type(path)
+}
+
+impl Client {
+    /// Read the contents of a file. See [cat](fn.cat.html).
+    pub fn cat(&self, path: &str) -> io::Result<CatReader> {
+        self.cat_range(path, 0, None)
+    }
+
+    /// Read part of a file. See [cat_range](cat/fn.cat_range.html).
+    pub fn cat_range(&self, path: &str, offset: u64, length: Option<u64>) -> io::Result<CatReader> {
+        match self.resolve(path, true).and_then(|path| self.cat_resolved(&path, offset, length)) {
+            Err(ref e) if api::is_connection_error(e) && self.gateway_fallback().is_some() => {
+                self.cat_fallback(path, offset, length)
+            }
+            r => r,
+        }
+    }
+
+    // Reads a file through the fallback gateway. Unlike `get_fallback`, the
+    // contents aren't verified (see `set_gateway_fallback`).
+    fn cat_fallback(&self, path: &str, offset: u64, length: Option<u64>) -> io::Result<CatReader> {
+        let path = if path.starts_with('/') {
+            path.to_owned()
+        } else {
+            format!("/ipfs/{}", path)
+        };
+        let mut response = match api::get_fallback(self, &path) {
+            Some(resp) => resp?.body,
+            None => return Err(io::Error::new(io::ErrorKind::NotConnected, "no fallback gateway")),
+        };
+        // Gateways don't necessarily support range requests.
+        io::copy(&mut (&mut response).take(offset), &mut io::sink())?;
+        Ok(CatReader {
+            response: response,
+            remaining: length,
+        })
+    }
+
+    fn cat_resolved(&self, path: &str, offset: u64, length: Option<u64>) -> io::Result<CatReader> {
+        let offset = offset.to_string();
+        let length_str = length.map(|l| l.to_string());
+        let mut args = vec![("offset", &offset[..])];
+        if let Some(ref length) = length_str {
+            args.push(("length", &length[..]));
+        }
+        args.push(("arg", path));
+        Ok(CatReader {
+            response: api::get_raw(self, "cat", &args)?,
+            remaining: length,
+        })
+    }
+
+    /// Open a file for random access. See [cat::open_file](cat/fn.open_file.html).
+    pub fn open_file(&self, path: &str) -> io::Result<UnixFsFile> {
+        let path = self.resolve(path, true)?;
+        let len = self.files_stat(&path)?.size;
+        Ok(UnixFsFile {
+            client: self.clone(),
+            path: path,
+            len: len,
+            pos: 0,
+            reader: None,
+        })
+    }
+
+    /// Read a file through the gateway. See
+    /// [cat::fetch_with_type](cat/fn.fetch_with_type.html).
+    pub fn fetch_with_type(&self, path: &str) -> io::Result<(CatReader, Option<Mime>)> {
+        let path = if path.starts_with('/') {
+            path.to_owned()
+        } else {
+            format!("/ipfs/{}", path)
+        };
+        let resp = api::get_gateway(self, &path)?;
+        let mime = resp.headers.get::<ContentType>().map(|c| c.0.clone());
+        Ok((CatReader {
+            response: resp.body,
+            remaining: None,
+        }, mime))
+    }
+}
@@ -0,0 +1,154 @@
+//! Minimal content identifier (CID) support.
+use std::fmt;
+
+use base58::{ToBase58, FromBase58};
+
+use multihash::{self, Multihash, CidError};
+
+/// The multicodec of merkledag (protobuf) objects.
+pub const DAG_PROTOBUF: u64 = 0x70;
+
+const BASE32_ALPHABET: &'static [u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// The CID version.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Version {
+    /// Legacy base58 encoded multihashes (`Qm...`).
+    V0,
+    /// Self-describing, multibase encoded CIDs (`bafy...`).
+    V1,
+}
+
+/// A parsed content identifier.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cid {
+    /// The CID version.
+    pub version: Version,
+    /// The multicodec of the content (always `DAG_PROTOBUF` for v0 CIDs).
+    pub codec: u64,
+    /// The content's multihash.
+    pub multihash: Multihash,
+}
+
+impl Cid {
+    /// Parse a CID from its string representation.
+    pub fn parse(s: &str) -> Result<Cid, CidError> {
+        if s.len() == 46 && s.starts_with("Qm") {
+            let bytes = s.from_base58().map_err(|_| CidError::InvalidEncoding)?;
+            return Ok(Cid {
+                version: Version::V0,
+                codec: DAG_PROTOBUF,
+                multihash: Multihash::from_bytes(&bytes)?,
+            });
+        }
+        let mut chars = s.chars();
+        let bytes = match chars.next() {
+            Some('b') => base32_decode(chars.as_str())?,
+            Some('z') => chars.as_str().from_base58().map_err(|_| CidError::InvalidEncoding)?,
+            _ => return Err(CidError::UnsupportedMultibase),
+        };
+        Cid::from_bytes(&bytes)
+    }
+
+    /// Decode a CID from its binary representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Cid, CidError> {
+        if bytes.len() == 34 && bytes[0] as u64 == multihash::SHA2_256 && bytes[1] == 32 {
+            return Ok(Cid {
+                version: Version::V0,
+                codec: DAG_PROTOBUF,
+                multihash: Multihash::from_bytes(bytes)?,
+            });
+        }
+        let mut rest = bytes;
+        if read_varint(&mut rest)? != 1 {
+            return Err(CidError::UnsupportedVersion);
+        }
+        let codec = read_varint(&mut rest)?;
+        Ok(Cid {
+            version: Version::V1,
+            codec: codec,
+            multihash: Multihash::from_bytes(rest)?,
+        })
+    }
+
+    /// Encode this CID into its binary representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.version {
+            Version::V0 => self.multihash.to_bytes(),
+            Version::V1 => {
+                let multihash = self.multihash.to_bytes();
+                let mut bytes = Vec::with_capacity(multihash.len() + 4);
+                write_varint(&mut bytes, 1);
+                write_varint(&mut bytes, self.codec);
+                bytes.extend_from_slice(&multihash);
+                bytes
+            }
+        }
+    }
+}
+
+impl fmt::Display for Cid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.version {
+            Version::V0 => f.write_str(&self.to_bytes().to_base58()),
+            Version::V1 => write!(f, "b{}", base32_encode(&self.to_bytes())),
+        }
+    }
+}
+
+pub fn read_varint(bytes: &mut &[u8]) -> Result<u64, CidError> {
+    let mut value = 0u64;
+    for (i, &b) in bytes.iter().enumerate().take(9) {
+        value |= ((b & 0x7f) as u64) << (7 * i);
+        if b & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Ok(value);
+        }
+    }
+    Err(CidError::InvalidVarint)
+}
+
+pub fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &b in bytes {
+        buffer = (buffer << 8) | b as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(s: &str) -> Result<Vec<u8>, CidError> {
+    let mut out = Vec::with_capacity(s.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let value = match BASE32_ALPHABET.iter().position(|&a| a == c) {
+            Some(v) => v as u32,
+            None => return Err(CidError::InvalidEncoding),
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(out)
+}
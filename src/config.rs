@@ -0,0 +1,53 @@
+//! API for reading and changing the IPFS node's configuration.
+use std::io;
+
+use serde_json::{self, Value};
+
+use api::{self, Client};
+use encoding::Json;
+
+#[derive(Deserialize)]
+struct ConfigResult {
+    #[serde(rename="Value")]
+    value: Value,
+}
+
+/// Get the configuration value at the given key (e.g. `Datastore.GCPeriod`).
+pub fn get_config(key: &str) -> io::Result<Value> {
+    api::default_client().get_config(key)
+}
+
+/// Set the configuration value at the given key.
+///
+/// If `json` is false, the value is stored as a string (non-string values are
+/// converted to their JSON representation). Otherwise, it's stored as is (use
+/// this for booleans, numbers, arrays, and objects).
+pub fn set_config(key: &str, value: &Value, json: bool) -> io::Result<()> {
+    api::default_client().set_config(key, value, json)
+}
+
+impl Client {
+    /// Get a configuration value. See [config::get_config](config/fn.get_config.html).
+    pub fn get_config(&self, key: &str) -> io::Result<Value> {
+        self.config(key, None, false)
+    }
+
+    /// Set a configuration value. See [config::set_config](config/fn.set_config.html).
+    pub fn set_config(&self, key: &str, value: &Value, json: bool) -> io::Result<()> {
+        let value = match *value {
+            Value::String(ref s) if !json => s.clone(),
+            ref v => serde_json::to_string(v).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        };
+        self.config(key, Some(&value), json).map(|_| ())
+    }
+
+    // Reads the value if `value` is None.
+    fn config(&self, key: &str, value: Option<&str>, json: bool) -> io::Result<Value> {
+        let mut args = vec![("arg", key)];
+        if let Some(value) = value {
+            args.push(("arg", value));
+            args.push(("json", api::bool_to_str(json)));
+        }
+        Ok(api::post::<Json, ConfigResult>(self, "config", &args)?.value)
+    }
+}
@@ -0,0 +1,97 @@
+//! API for storing structured (IPLD) data.
+use std::io;
+
+use serde;
+use serde_json;
+
+use api::{self, Client};
+use object::Reference;
+use encoding::Json;
+
+/// The codec used to store a DAG node.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DagCodec {
+    /// Store the node as CBOR.
+    DagCbor,
+    /// Store the node as JSON.
+    DagJson,
+}
+
+impl DagCodec {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            DagCodec::DagCbor => "dag-cbor",
+            DagCodec::DagJson => "dag-json",
+        }
+    }
+}
+
+/// Store a value as a DAG node.
+///
+/// The returned reference's size is the size of the stored node (it doesn't
+/// include the size of any linked nodes).
+pub fn dag_put<T: serde::Serialize>(value: &T, codec: DagCodec) -> io::Result<Reference> {
+    api::default_client().dag_put(value, codec)
+}
+
+/// Get a DAG node and deserialize it.
+pub fn dag_get<T: serde::Deserialize>(path: &str) -> io::Result<T> {
+    api::default_client().dag_get(path)
+}
+
+/// Resolve an IPLD path (e.g. `/ipfs/bafy.../foo/bar` or `/ipld/bafy.../foo`)
+/// to the last node it passes through, returning a reference to that node and
+/// the rest of the path within it.
+///
+/// Unlike [object::lookup](../object/fn.lookup.html), this can traverse fields
+/// of structured (e.g. CBOR) nodes. Pass the reference and the remaining path
+/// to [dag_get](fn.dag_get.html) to read the value. The reference's size is the
+/// size of the node itself.
+pub fn dag_resolve(path: &str) -> io::Result<(Reference, String)> {
+    api::default_client().dag_resolve(path)
+}
+
+#[derive(Deserialize)]
+struct CidLink {
+    #[serde(rename="/")]
+    cid: String,
+}
+
+impl Client {
+    /// Store a value as a DAG node. See [dag::dag_put](dag/fn.dag_put.html).
+    pub fn dag_put<T: serde::Serialize>(&self, value: &T, codec: DagCodec) -> io::Result<Reference> {
+        #[derive(Deserialize)]
+        struct PutResult {
+            #[serde(rename="Cid")]
+            cid: CidLink,
+        }
+
+        let data = serde_json::to_vec(value);
+        let resp = api::post_data::<Json, PutResult>(self, "dag/put", &[
+            ("store-codec", codec.as_str()),
+            ("input-codec", "dag-json"),
+        ], &data)?;
+        let size = self.stat_block(&resp.cid.cid)?.size;
+        Ok(Reference::new(resp.cid.cid, size).with_client(self))
+    }
+
+    /// Get a DAG node and deserialize it. See [dag::dag_get](dag/fn.dag_get.html).
+    pub fn dag_get<T: serde::Deserialize>(&self, path: &str) -> io::Result<T> {
+        Ok(api::get::<Json, T>(self, "dag/get", &[("arg", path)])?)
+    }
+
+    /// Resolve an IPLD path. See [dag::dag_resolve](dag/fn.dag_resolve.html).
+    pub fn dag_resolve(&self, path: &str) -> io::Result<(Reference, String)> {
+        #[derive(Deserialize)]
+        struct ResolveResult {
+            #[serde(rename="Cid")]
+            cid: CidLink,
+            #[serde(rename="RemPath", default)]
+            rem_path: String,
+        }
+
+        let resp = api::get::<Json, ResolveResult>(self, "dag/resolve", &[("arg", path)])?;
+        let size = self.stat_block(&resp.cid.cid)?.size;
+        Ok((Reference::new(resp.cid.cid, size).with_client(self), resp.rem_path))
+    }
+}
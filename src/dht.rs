@@ -0,0 +1,157 @@
+//! API for querying the DHT.
+use std::io::{self, Read};
+
+use rustc_serialize::base64::FromBase64;
+
+use api::{self, Client};
+use encoding::JsonStream;
+use version::VersionNumber;
+
+/// A peer ID.
+pub type PeerId = String;
+
+// DHT query event types.
+const EVENT_FINAL_PEER: u32 = 2;
+const EVENT_PROVIDER: u32 = 4;
+const EVENT_QUERY_ERROR: u32 = 3;
+const EVENT_VALUE: u32 = 5;
+
+#[derive(Deserialize)]
+struct QueryEvent {
+    #[serde(rename="Type")]
+    kind: u32,
+    #[serde(rename="Responses", default)]
+    responses: Option<Vec<PeerResponse>>,
+    #[serde(rename="Extra", default)]
+    extra: String,
+}
+
+#[derive(Deserialize)]
+struct PeerResponse {
+    #[serde(rename="ID")]
+    id: PeerId,
+    #[serde(rename="Addrs", default)]
+    addrs: Option<Vec<String>>,
+}
+
+/// Find the peers providing the given hash.
+///
+/// Stops after finding `num` providers (20 by default).
+pub fn find_providers(hash: &str, num: Option<u32>) -> io::Result<Vec<PeerId>> {
+    api::default_client().find_providers(hash, num)
+}
+
+/// Find the addresses of the given peer.
+pub fn find_peer(peer: &str) -> io::Result<Vec<String>> {
+    api::default_client().find_peer(peer)
+}
+
+/// Get the value stored in the DHT under the given key.
+///
+/// Keys must be in the `/<namespace>/<key>` form accepted by the IPFS node's
+/// record validators (e.g. `/ipns/<peer id>`); arbitrary keys are rejected.
+pub fn dht_get(key: &str) -> io::Result<Vec<u8>> {
+    api::default_client().dht_get(key)
+}
+
+/// Store a value in the DHT under the given key.
+///
+/// The value must be a record that validates under the key's namespace. See
+/// [dht_get](fn.dht_get.html).
+pub fn dht_put(key: &str, value: &[u8]) -> io::Result<()> {
+    api::default_client().dht_put(key, value)
+}
+
+// Reads query events until the query ends, returning the last value and the
+// last query error (if any).
+fn read_events(resp: Box<Read + Send>) -> io::Result<(Option<String>, Option<String>)> {
+    let mut value = None;
+    let mut error = None;
+    for event in JsonStream::<QueryEvent>::new(resp) {
+        let event = event?;
+        match event.kind {
+            EVENT_VALUE => value = Some(event.extra),
+            EVENT_QUERY_ERROR if !event.extra.is_empty() => error = Some(event.extra),
+            _ => (),
+        }
+    }
+    Ok((value, error))
+}
+
+impl Client {
+    /// Find the peers providing the given hash. See
+    /// [dht::find_providers](dht/fn.find_providers.html).
+    pub fn find_providers(&self, hash: &str, num: Option<u32>) -> io::Result<Vec<PeerId>> {
+        let num = num.map(|n| n.to_string());
+        let mut args = vec![("encoding", "json"), ("arg", hash)];
+        if let Some(ref num) = num {
+            args.push(("num-providers", &num[..]));
+        }
+
+        let mut providers = Vec::new();
+        for event in JsonStream::<QueryEvent>::new(api::get_raw(self, "dht/findprovs", &args)?) {
+            let event = event?;
+            if event.kind != EVENT_PROVIDER {
+                continue;
+            }
+            for r in event.responses.unwrap_or_else(Vec::new) {
+                if !providers.contains(&r.id) {
+                    providers.push(r.id);
+                }
+            }
+        }
+        Ok(providers)
+    }
+
+    /// Find the addresses of the given peer. See
+    /// [dht::find_peer](dht/fn.find_peer.html).
+    pub fn find_peer(&self, peer: &str) -> io::Result<Vec<String>> {
+        let resp = api::get_raw(self, "dht/findpeer", &[("encoding", "json"), ("arg", peer)])?;
+        let mut error = None;
+        for event in JsonStream::<QueryEvent>::new(resp) {
+            let event = event?;
+            match event.kind {
+                EVENT_FINAL_PEER => {
+                    for r in event.responses.unwrap_or_else(Vec::new) {
+                        if r.id == peer {
+                            return Ok(r.addrs.unwrap_or_else(Vec::new));
+                        }
+                    }
+                }
+                EVENT_QUERY_ERROR if !event.extra.is_empty() => error = Some(event.extra),
+                _ => (),
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::NotFound,
+                           error.unwrap_or_else(|| "peer not found".to_owned())))
+    }
+
+    /// Get a value from the DHT. See [dht::dht_get](dht/fn.dht_get.html).
+    pub fn dht_get(&self, key: &str) -> io::Result<Vec<u8>> {
+        let resp = api::get_raw(self, "dht/get", &[("encoding", "json"), ("arg", key)])?;
+        // The final value wins.
+        match read_events(resp)? {
+            (Some(value), _) => {
+                // Older nodes put the raw value in the event instead of
+                // base64-encoding it.
+                if api::node_version(self)? < VersionNumber::new(0, 5, 0) {
+                    return Ok(value.into_bytes());
+                }
+                value.from_base64().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            (None, error) => {
+                Err(io::Error::new(io::ErrorKind::NotFound,
+                                   error.unwrap_or_else(|| "value not found".to_owned())))
+            }
+        }
+    }
+
+    /// Store a value in the DHT. See [dht::dht_put](dht/fn.dht_put.html).
+    pub fn dht_put(&self, key: &str, value: &[u8]) -> io::Result<()> {
+        let resp = api::post_data_raw(self, "dht/put", &[("arg", key)], value)?;
+        match read_events(resp)? {
+            (_, Some(error)) => Err(io::Error::new(io::ErrorKind::Other, error)),
+            (_, None) => Ok(()),
+        }
+    }
+}
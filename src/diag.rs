@@ -0,0 +1,68 @@
+//! API for diagnosing the IPFS node.
+use std::io;
+use std::time::Duration;
+
+use api::{self, Client};
+use encoding::{Ignore, Json};
+
+/// An API command that's running on (or recently ran on) the IPFS node.
+///
+/// Returned from [diag_cmds](fn.diag_cmds.html).
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct ActiveCommand {
+    /// The command's ID.
+    #[serde(rename="ID")]
+    pub id: u64,
+    /// The command's name (e.g. `object/stat`).
+    #[serde(rename="Command")]
+    pub command: String,
+    /// When the command started (RFC 3339).
+    #[serde(rename="StartTime")]
+    pub start_time: String,
+    /// When the command finished (RFC 3339), if it has.
+    #[serde(rename="EndTime", default)]
+    pub end_time: Option<String>,
+    /// Whether the command is still running.
+    #[serde(rename="Active")]
+    pub active: bool,
+}
+
+/// List the commands running on the IPFS node along with recently finished
+/// commands.
+///
+/// With `verbose` set, the node includes the commands' arguments and options
+/// in its response (these aren't exposed yet).
+pub fn diag_cmds(verbose: bool) -> io::Result<Vec<ActiveCommand>> {
+    api::default_client().diag_cmds(verbose)
+}
+
+/// Forget recently finished commands.
+pub fn diag_cmds_clear() -> io::Result<()> {
+    api::default_client().diag_cmds_clear()
+}
+
+/// Set how long the IPFS node remembers finished commands.
+pub fn diag_cmds_set_time(time: Duration) -> io::Result<()> {
+    api::default_client().diag_cmds_set_time(time)
+}
+
+impl Client {
+    /// List the commands running on the IPFS node. See
+    /// [diag::diag_cmds](diag/fn.diag_cmds.html).
+    pub fn diag_cmds(&self, verbose: bool) -> io::Result<Vec<ActiveCommand>> {
+        let cmds = api::get::<Json, Option<Vec<ActiveCommand>>>(self, "diag/cmds", &[("verbose", api::bool_to_str(verbose))])?;
+        Ok(cmds.unwrap_or_else(Vec::new))
+    }
+
+    /// Forget recently finished commands. See
+    /// [diag::diag_cmds_clear](diag/fn.diag_cmds_clear.html).
+    pub fn diag_cmds_clear(&self) -> io::Result<()> {
+        Ok(api::post::<Ignore, ()>(self, "diag/cmds/clear", &[])?)
+    }
+
+    /// Set how long the IPFS node remembers finished commands. See
+    /// [diag::diag_cmds_set_time](diag/fn.diag_cmds_set_time.html).
+    pub fn diag_cmds_set_time(&self, time: Duration) -> io::Result<()> {
+        Ok(api::post::<Ignore, ()>(self, "diag/cmds/set-time", &[("arg", &api::duration_to_str(time))])?)
+    }
+}
@@ -0,0 +1,124 @@
+//! Building UnixFS directories out of existing objects.
+//!
+//! A [Directory](struct.Directory.html) is built up in memory and only talks to
+//! the IPFS node when it's committed. The committed directories can be browsed
+//! like the ones created by `ipfs add -r` (e.g. with
+//! [unixfs::ls](../unixfs/fn.ls.html) or through a gateway).
+use std::io;
+use std::collections::BTreeMap;
+
+use protobuf::Message;
+
+use api::{self, Client};
+use object::{Object, Reference};
+use unixfs_pb;
+
+/// An in-memory UnixFS directory tree.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Directory {
+    entries: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Entry {
+    Target(Reference),
+    Directory(Directory),
+}
+
+fn split(path: &str) -> io::Result<Vec<&str>> {
+    let parts: Vec<_> = path.trim_matches('/').split('/').collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid directory path"));
+    }
+    Ok(parts)
+}
+
+impl Directory {
+    /// Create an empty directory.
+    pub fn new() -> Directory {
+        Directory { entries: BTreeMap::new() }
+    }
+
+    /// Insert a file (or an existing directory) at the given slash-separated
+    /// path, creating any missing parent directories.
+    ///
+    /// Replaces anything already at that path. Fails with an
+    /// `io::ErrorKind::InvalidInput` error if the path is empty or if one of
+    /// the parents has already been set to a reference.
+    pub fn insert(&mut self, path: &str, target: Reference) -> io::Result<()> {
+        let parts = split(path)?;
+        let (name, parents) = parts.split_last().unwrap();
+        let mut dir = self;
+        for parent in parents {
+            // Move the borrow so it can be replaced by a borrow of the child.
+            let current = dir;
+            let entry = current.entries
+                .entry((*parent).to_owned())
+                .or_insert_with(|| Entry::Directory(Directory::new()));
+            dir = match *entry {
+                Entry::Directory(ref mut dir) => dir,
+                Entry::Target(_) => {
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                              format!("{} is not a directory", parent)))
+                }
+            };
+        }
+        dir.entries.insert((*name).to_owned(), Entry::Target(target));
+        Ok(())
+    }
+
+    /// Get the reference inserted at the given path.
+    ///
+    /// Returns `None` for directories created by `insert`.
+    pub fn get(&self, path: &str) -> Option<&Reference> {
+        let parts = match split(path) {
+            Ok(parts) => parts,
+            Err(_) => return None,
+        };
+        let mut dir = self;
+        let (name, parents) = parts.split_last().unwrap();
+        for parent in parents {
+            dir = match dir.entries.get(*parent) {
+                Some(&Entry::Directory(ref dir)) => dir,
+                _ => return None,
+            };
+        }
+        match dir.entries.get(*name) {
+            Some(&Entry::Target(ref target)) => Some(target),
+            _ => None,
+        }
+    }
+
+    /// Check whether this directory is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Commit this directory (and any subdirectories) to IPFS.
+    pub fn commit(&self) -> io::Result<Reference> {
+        api::default_client().commit_directory(self)
+    }
+}
+
+impl Client {
+    /// Commit a directory to IPFS. See
+    /// [Directory::commit](dir/struct.Directory.html#method.commit).
+    pub fn commit_directory(&self, dir: &Directory) -> io::Result<Reference> {
+        let mut data = unixfs_pb::Data::new();
+        data.set_Type(unixfs_pb::Data_DataType::Directory);
+
+        let mut object = Object {
+            data: data.write_to_bytes().unwrap(),
+            links: Vec::with_capacity(dir.entries.len()),
+        };
+        // Directory entries are sorted by name (the map takes care of that).
+        for (name, entry) in &dir.entries {
+            let target = match *entry {
+                Entry::Target(ref target) => target.clone(),
+                Entry::Directory(ref dir) => self.commit_directory(dir)?,
+            };
+            object.link(name.clone(), target)?;
+        }
+        Ok(self.commit(object)?.into())
+    }
+}
@@ -1,46 +1,202 @@
-use std::io::{self, Read};
+use std::io::{self, BufRead, BufReader, Read};
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 use protobuf::{self, MessageStatic};
 use serde;
 use serde_json;
 
+use error::{Error, Result};
+
 pub trait Encoding<T> {
     const ENCODING: Option<&'static str>;
-    fn parse(reader: &mut Read) -> io::Result<T>;
+    /// Parse a response body, given its length if known (from the
+    /// `Content-Length` header).
+    fn parse(reader: &mut Read, len: Option<u64>) -> Result<T>;
 }
 
+// Don't trust the IPFS node with preallocating more than this.
+const MAX_PREALLOCATE: u64 = 16 * 1024 * 1024;
+
 pub struct Json;
 pub struct Ignore;
 pub struct Protobuf;
+/// The raw response body.
+pub struct Raw;
 
 impl Encoding<()> for Ignore {
     const ENCODING: Option<&'static str> = None;
-    fn parse(_: &mut Read) -> io::Result<()> {
+    fn parse(_: &mut Read, _: Option<u64>) -> Result<()> {
         Ok(())
     }
 }
 
+impl Encoding<Vec<u8>> for Raw {
+    const ENCODING: Option<&'static str> = None;
+    fn parse(r: &mut Read, len: Option<u64>) -> Result<Vec<u8>> {
+        match len {
+            // Read the body straight into a buffer of the right size instead
+            // of growing it piece by piece.
+            Some(len) if len <= MAX_PREALLOCATE => {
+                let mut data = vec![0; len as usize];
+                r.read_exact(&mut data)?;
+                Ok(data)
+            }
+            _ => {
+                let mut data = Vec::new();
+                r.read_to_end(&mut data)?;
+                Ok(data)
+            }
+        }
+    }
+}
+
 impl<T: serde::Deserialize> Encoding<T> for Json {
     const ENCODING: Option<&'static str> = Some("json");
-    fn parse(r: &mut Read) -> io::Result<T> {
+    fn parse(r: &mut Read, _: Option<u64>) -> Result<T> {
         use serde_json::error::Error::Io;
         serde_json::from_reader(r).map_err(|e| {
             match e {
-                Io(e) => io::Error::new(io::ErrorKind::InvalidData, e),
-                e => io::Error::new(io::ErrorKind::InvalidData, e),
+                Io(e) => e.into(),
+                e => Error::Parse(e.into()),
             }
         })
     }
 }
 
+/// A streaming iterator over newline delimited JSON values.
+///
+/// Used for every endpoint that streams its results: values are parsed as they
+/// arrive. Blank lines are skipped. If the stream is malformed, the iterator
+/// yields an error and then stops.
+pub struct JsonStream<T> {
+    lines: Lines,
+    timeout: Option<Duration>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+enum Lines {
+    Direct(BufReader<Box<Read + Send>>),
+    // Read by a background thread so that we can stop waiting for a line
+    // without losing it.
+    Threaded(mpsc::Receiver<io::Result<String>>),
+    Done,
+}
+
+impl<T> JsonStream<T> {
+    pub fn new(reader: Box<Read + Send>) -> JsonStream<T> {
+        JsonStream {
+            lines: Lines::Direct(BufReader::new(reader)),
+            timeout: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Give up waiting for the next value after `timeout`, yielding an
+    /// `io::ErrorKind::TimedOut` error. The stream can still be read
+    /// afterwards.
+    ///
+    /// Setting a timeout moves the reading into a background thread which owns
+    /// the connection. Dropping the iterator doesn't close the connection: the
+    /// thread only exits (closing it) once the next line arrives or the node
+    /// closes the connection.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+        if timeout.is_none() {
+            return;
+        }
+        self.lines = match mem::replace(&mut self.lines, Lines::Done) {
+            Lines::Direct(reader) => {
+                let (tx, rx) = mpsc::channel();
+                thread::spawn(move || {
+                    for line in reader.lines() {
+                        let failed = line.is_err();
+                        if tx.send(line).is_err() || failed {
+                            break;
+                        }
+                    }
+                });
+                Lines::Threaded(rx)
+            }
+            lines => lines,
+        };
+    }
+
+    /// Get the read timeout.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn next_line(&mut self) -> Option<io::Result<String>> {
+        let line = match self.lines {
+            Lines::Direct(ref mut reader) => {
+                let mut line = String::new();
+                match reader.read_line(&mut line) {
+                    Ok(0) => None,
+                    Ok(_) => Some(Ok(line)),
+                    Err(e) => Some(Err(e)),
+                }
+            }
+            Lines::Threaded(ref rx) => {
+                match self.timeout {
+                    Some(timeout) => {
+                        match rx.recv_timeout(timeout) {
+                            Ok(line) => Some(line),
+                            Err(mpsc::RecvTimeoutError::Timeout) => {
+                                return Some(Err(io::Error::new(io::ErrorKind::TimedOut,
+                                                               "timed out waiting for the next value")));
+                            }
+                            Err(mpsc::RecvTimeoutError::Disconnected) => None,
+                        }
+                    }
+                    None => rx.recv().ok(),
+                }
+            }
+            Lines::Done => None,
+        };
+        match line {
+            Some(Ok(line)) => Some(Ok(line)),
+            other => {
+                self.lines = Lines::Done;
+                other
+            }
+        }
+    }
+}
+
+impl<T: serde::Deserialize> Iterator for JsonStream<T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<io::Result<T>> {
+        loop {
+            let line = match self.next_line() {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Some(Err(e)),
+                None => return None,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value = <Json as Encoding<T>>::parse(&mut line.as_bytes(), None);
+            if value.is_err() {
+                self.lines = Lines::Done;
+            }
+            return Some(value.map_err(io::Error::from));
+        }
+    }
+}
+
 impl<T: MessageStatic> Encoding<T> for Protobuf {
     const ENCODING: Option<&'static str> = Some("protobuf");
 
-    fn parse(r: &mut Read) -> io::Result<T> {
+    fn parse(r: &mut Read, _: Option<u64>) -> Result<T> {
         use protobuf::ProtobufError::*;
         protobuf::parse_from_reader::<T>(r).map_err(|e| {
             match e {
-                IoError(e) => e,
-                WireError(e) => io::Error::new(io::ErrorKind::InvalidData, e),
+                IoError(e) => e.into(),
+                WireError(e) => Error::Parse(e.into()),
             }
         })
     }
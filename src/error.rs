@@ -0,0 +1,132 @@
+//! The error type returned by the IPFS API.
+use std::io;
+use std::fmt;
+use std::error::Error as StdError;
+
+use hyper;
+
+/// The error code for generic errors.
+pub const ERR_NORMAL: u32 = 0;
+/// The error code for errors caused by the client's request.
+pub const ERR_CLIENT: u32 = 1;
+/// The error code for internal errors in the IPFS node.
+pub const ERR_IMPLEMENTATION: u32 = 2;
+/// The error code for requests for something that doesn't exist.
+pub const ERR_NOT_FOUND: u32 = 3;
+/// The error code for fatal errors.
+pub const ERR_FATAL: u32 = 4;
+
+/// An IPFS API error.
+#[derive(Debug)]
+pub enum Error {
+    /// An IO error occurred while talking to the IPFS node.
+    Io(io::Error),
+    /// The IPFS node returned an error.
+    Api {
+        /// The error code.
+        code: u32,
+        /// The error message.
+        message: String,
+    },
+    /// The request failed with an HTTP status but without an IPFS error
+    /// message (e.g. a `502 Bad Gateway` page from a proxy in front of the
+    /// IPFS node).
+    Status {
+        /// The HTTP status code.
+        status: u16,
+        /// The start of the response body.
+        body: String,
+    },
+    /// The IPFS node returned a malformed response.
+    Parse(Box<StdError + Send + Sync>),
+    /// The HTTP request failed.
+    Http(hyper::Error),
+}
+
+impl Error {
+    /// Returns the error code reported by the IPFS node, if any (see the
+    /// `ERR_*` constants).
+    pub fn code(&self) -> Option<u32> {
+        match *self {
+            Error::Api { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this is an API error with the given message.
+    pub fn is_api_error(&self, msg: &str) -> bool {
+        match *self {
+            Error::Api { ref message, .. } => message == msg,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => e.fmt(f),
+            Error::Api { code, ref message } => write!(f, "{} (code {})", message, code),
+            Error::Status { status, ref body } if body.is_empty() => write!(f, "HTTP status {}", status),
+            Error::Status { status, ref body } => write!(f, "HTTP status {}: {}", status, body),
+            Error::Parse(ref e) => write!(f, "failed to parse response: {}", e),
+            Error::Http(ref e) => e.fmt(f),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Io(ref e) => e.description(),
+            Error::Api { ref message, .. } => message,
+            Error::Status { .. } => "unexpected HTTP status",
+            Error::Parse(ref e) => e.description(),
+            Error::Http(ref e) => e.description(),
+        }
+    }
+    fn cause(&self) -> Option<&StdError> {
+        match *self {
+            Error::Io(ref e) => e.cause(),
+            Error::Api { .. } => None,
+            Error::Status { .. } => None,
+            Error::Parse(ref e) => Some(&**e),
+            Error::Http(ref e) => e.cause(),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        // Socket timeouts surface as `WouldBlock` on some platforms.
+        if e.kind() == io::ErrorKind::WouldBlock {
+            Error::Io(io::Error::new(io::ErrorKind::TimedOut, e))
+        } else {
+            Error::Io(e)
+        }
+    }
+}
+
+impl From<hyper::Error> for Error {
+    fn from(e: hyper::Error) -> Error {
+        match e {
+            hyper::Error::Io(e) => e.into(),
+            e => Error::Http(e),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        match e {
+            Error::Io(e) => e,
+            Error::Api { message, .. } => io::Error::new(io::ErrorKind::Other, message),
+            e @ Error::Status { .. } => io::Error::new(io::ErrorKind::Other, e),
+            Error::Parse(e) => io::Error::new(io::ErrorKind::InvalidData, e),
+            Error::Http(e) => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+/// A specialized result type for IPFS API calls.
+pub type Result<T> = ::std::result::Result<T, Error>;
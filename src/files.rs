@@ -0,0 +1,267 @@
+//! API for the mutable filesystem (MFS).
+//!
+//! These functions operate on `/`-rooted MFS paths, not on hashes.
+use std::io::{self, Read};
+
+
+use api::{self, Client};
+use error::Error;
+use object::Reference;
+use unixfs::{DirEntry, EntryKind};
+use encoding::{Ignore, Json};
+
+/// A streaming reader over the contents of an MFS file.
+///
+/// Returned from [files_read](fn.files_read.html).
+pub struct FileReader {
+    response: Box<Read + Send>,
+}
+
+impl Read for FileReader {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.response.read(buf).map_err(|e| io::Error::from(Error::from(e)))
+    }
+}
+
+/// Options for [files_write](fn.files_write.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct WriteOptions {
+    /// Create the file if it doesn't exist.
+    pub create: bool,
+    /// Truncate the file before writing.
+    pub truncate: bool,
+    /// The offset at which to start writing.
+    pub offset: Option<u64>,
+}
+
+/// Information about an MFS file or directory.
+///
+/// Returned from [files_stat](fn.files_stat.html).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FilesStat {
+    /// The hash of the file or directory.
+    pub hash: String,
+    /// The size of the file's contents.
+    pub size: u64,
+    /// The total size of the file or directory and its children.
+    pub cumulative_size: u64,
+    /// The number of blocks directly linked to by the file or directory.
+    pub blocks: u64,
+    /// Whether this is a file or a directory.
+    pub kind: EntryKind,
+}
+
+/// Read the contents of the file at the given MFS path.
+pub fn files_read(path: &str) -> io::Result<FileReader> {
+    api::default_client().files_read(path)
+}
+
+/// Write data to the file at the given MFS path.
+pub fn files_write(path: &str, data: &[u8], opts: WriteOptions) -> io::Result<()> {
+    api::default_client().files_write(path, data, opts)
+}
+
+/// Create a directory at the given MFS path.
+///
+/// If `parents` is true, missing parent directories are created too (and it's
+/// not an error if the directory already exists).
+pub fn files_mkdir(path: &str, parents: bool) -> io::Result<()> {
+    api::default_client().files_mkdir(path, parents)
+}
+
+/// List the entries in the MFS directory at the given path.
+pub fn files_ls(path: &str) -> io::Result<Vec<DirEntry>> {
+    api::default_client().files_ls(path)
+}
+
+/// Get information about the MFS file or directory at the given path.
+pub fn files_stat(path: &str) -> io::Result<FilesStat> {
+    api::default_client().files_stat(path)
+}
+
+/// Remove the MFS file or directory at the given path.
+///
+/// Directories can only be removed if `recursive` is true.
+pub fn files_rm(path: &str, recursive: bool) -> io::Result<()> {
+    api::default_client().files_rm(path, recursive)
+}
+
+/// Copy a file or directory into MFS.
+///
+/// The source may be an MFS path or an `/ipfs/` path.
+pub fn files_cp(source: &str, dest: &str) -> io::Result<()> {
+    api::default_client().files_cp(source, dest)
+}
+
+/// Move an MFS file or directory.
+pub fn files_mv(source: &str, dest: &str) -> io::Result<()> {
+    api::default_client().files_mv(source, dest)
+}
+
+/// Flush the MFS file or directory at the given path to the IPFS node's
+/// blockstore, returning a reference to it.
+///
+/// Use this to get a stable reference to an MFS tree (e.g. `/`) that can be
+/// published with [name::publish](../name/fn.publish.html).
+pub fn files_flush(path: &str) -> io::Result<Reference> {
+    api::default_client().files_flush(path)
+}
+
+/// Change the CID version (0 or 1) and/or hash function (e.g. "sha2-256") of
+/// the MFS directory at the given path.
+///
+/// Only the directory itself (and any directories modified afterwards) is
+/// re-encoded; existing children keep their CIDs.
+pub fn files_chcid(path: &str, cid_version: u8, hash: Option<&str>) -> io::Result<()> {
+    api::default_client().files_chcid(path, cid_version, hash)
+}
+
+impl Client {
+    /// Read the contents of the file at the given MFS path. See
+    /// [files::files_read](files/fn.files_read.html).
+    pub fn files_read(&self, path: &str) -> io::Result<FileReader> {
+        Ok(FileReader {
+            response: api::get_raw(self, "files/read", &[("arg", path)])?,
+        })
+    }
+
+    /// Write data to the file at the given MFS path. See
+    /// [files::files_write](files/fn.files_write.html).
+    pub fn files_write(&self, path: &str, data: &[u8], opts: WriteOptions) -> io::Result<()> {
+        let offset = opts.offset.map(|o| o.to_string());
+        let mut args = vec![
+            ("create", api::bool_to_str(opts.create)),
+            ("truncate", api::bool_to_str(opts.truncate)),
+        ];
+        if let Some(ref offset) = offset {
+            args.push(("offset", &offset[..]));
+        }
+        args.push(("arg", path));
+        Ok(api::post_data::<Ignore, ()>(self, "files/write", &args, data)?)
+    }
+
+    /// Create a directory at the given MFS path. See
+    /// [files::files_mkdir](files/fn.files_mkdir.html).
+    pub fn files_mkdir(&self, path: &str, parents: bool) -> io::Result<()> {
+        Ok(api::post::<Ignore, ()>(self, "files/mkdir", &[("parents", api::bool_to_str(parents)), ("arg", path)])?)
+    }
+
+    /// List the entries in the MFS directory at the given path. See
+    /// [files::files_ls](files/fn.files_ls.html).
+    pub fn files_ls(&self, path: &str) -> io::Result<Vec<DirEntry>> {
+        #[derive(Deserialize)]
+        struct LsResult {
+            #[serde(rename="Entries", default)]
+            entries: Option<Vec<LsEntry>>,
+        }
+
+        #[derive(Deserialize)]
+        struct LsEntry {
+            #[serde(rename="Name")]
+            name: String,
+            #[serde(rename="Type")]
+            kind: u64,
+            #[serde(rename="Size")]
+            size: u64,
+            #[serde(rename="Hash")]
+            hash: String,
+        }
+
+        let resp = api::get::<Json, LsResult>(self, "files/ls", &[("long", "true"), ("arg", path)])?;
+        Ok(resp.entries
+               .unwrap_or_else(Vec::new)
+               .into_iter()
+               .map(|e| {
+                   DirEntry {
+                       name: e.name,
+                       hash: e.hash,
+                       size: e.size,
+                       kind: match e.kind {
+                           0 => EntryKind::File,
+                           1 => EntryKind::Directory,
+                           k => EntryKind::Other(k),
+                       },
+                   }
+               })
+               .collect())
+    }
+
+    /// Get information about the MFS file or directory at the given path. See
+    /// [files::files_stat](files/fn.files_stat.html).
+    pub fn files_stat(&self, path: &str) -> io::Result<FilesStat> {
+        #[derive(Deserialize)]
+        struct StatResult {
+            #[serde(rename="Hash")]
+            hash: String,
+            #[serde(rename="Size")]
+            size: u64,
+            #[serde(rename="CumulativeSize")]
+            cumulative_size: u64,
+            #[serde(rename="Blocks")]
+            blocks: u64,
+            #[serde(rename="Type")]
+            kind: String,
+        }
+
+        let resp = api::get::<Json, StatResult>(self, "files/stat", &[("arg", path)])?;
+        Ok(FilesStat {
+            hash: resp.hash,
+            size: resp.size,
+            cumulative_size: resp.cumulative_size,
+            blocks: resp.blocks,
+            kind: match &*resp.kind {
+                "file" => EntryKind::File,
+                "directory" => EntryKind::Directory,
+                _ => EntryKind::Other(0),
+            },
+        })
+    }
+
+    /// Remove the MFS file or directory at the given path. See
+    /// [files::files_rm](files/fn.files_rm.html).
+    pub fn files_rm(&self, path: &str, recursive: bool) -> io::Result<()> {
+        Ok(api::post::<Ignore, ()>(self, "files/rm", &[("recursive", api::bool_to_str(recursive)), ("arg", path)])?)
+    }
+
+    /// Copy a file or directory into MFS. See [files::files_cp](files/fn.files_cp.html).
+    pub fn files_cp(&self, source: &str, dest: &str) -> io::Result<()> {
+        Ok(api::post::<Ignore, ()>(self, "files/cp", &[("arg", source), ("arg", dest)])?)
+    }
+
+    /// Move an MFS file or directory. See [files::files_mv](files/fn.files_mv.html).
+    pub fn files_mv(&self, source: &str, dest: &str) -> io::Result<()> {
+        Ok(api::post::<Ignore, ()>(self, "files/mv", &[("arg", source), ("arg", dest)])?)
+    }
+
+    /// Flush an MFS file or directory. See
+    /// [files::files_flush](files/fn.files_flush.html).
+    pub fn files_flush(&self, path: &str) -> io::Result<Reference> {
+        #[derive(Deserialize)]
+        struct FlushResult {
+            #[serde(rename="Cid", default)]
+            cid: Option<String>,
+        }
+
+        let resp = api::post::<Json, FlushResult>(self, "files/flush", &[("arg", path)])?;
+        match resp.cid {
+            Some(cid) => self.lookup(&cid),
+            // Older IPFS nodes don't return the CID.
+            None => {
+                let stat = self.files_stat(path)?;
+                Ok(Reference::new(stat.hash, stat.cumulative_size).with_client(self))
+            }
+        }
+    }
+
+    /// Change the CID version of an MFS directory. See
+    /// [files::files_chcid](files/fn.files_chcid.html).
+    pub fn files_chcid(&self, path: &str, cid_version: u8, hash: Option<&str>) -> io::Result<()> {
+        let cid_version = cid_version.to_string();
+        let mut args = vec![("arg", path), ("cid-version", &cid_version[..])];
+        if let Some(hash) = hash {
+            args.push(("hash", hash));
+        }
+        Ok(api::post::<Ignore, ()>(self, "files/chcid", &args)?)
+    }
+}
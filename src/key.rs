@@ -0,0 +1,98 @@
+//! API for managing IPNS keys.
+use std::io;
+
+use api::{self, Client};
+use encoding::Json;
+
+/// An IPNS key.
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Key {
+    /// The key's name.
+    #[serde(rename="Name")]
+    pub name: String,
+    /// The key's ID (the name under which it publishes).
+    #[serde(rename="Id")]
+    pub id: String,
+}
+
+/// The type of key to generate.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KeyType {
+    /// An RSA key with the given number of bits.
+    Rsa(u32),
+    /// An Ed25519 key.
+    Ed25519,
+}
+
+/// List this node's keys.
+pub fn list_keys() -> io::Result<Vec<Key>> {
+    api::default_client().list_keys()
+}
+
+/// Generate a new key with the given name.
+pub fn gen_key(name: &str, key_type: KeyType) -> io::Result<Key> {
+    api::default_client().gen_key(name, key_type)
+}
+
+/// Rename a key.
+///
+/// Returns the key's old and new names. Fails if a key named `new` already
+/// exists unless `force` is set (in which case the existing key is replaced).
+pub fn rename_key(old: &str, new: &str, force: bool) -> io::Result<(String, String)> {
+    api::default_client().rename_key(old, new, force)
+}
+
+/// Remove a key, returning the removed keys.
+pub fn rm_key(name: &str) -> io::Result<Vec<Key>> {
+    api::default_client().rm_key(name)
+}
+
+#[derive(Deserialize)]
+struct KeyList {
+    #[serde(rename="Keys", default)]
+    keys: Option<Vec<Key>>,
+}
+
+impl Client {
+    /// List this node's keys. See [key::list_keys](key/fn.list_keys.html).
+    pub fn list_keys(&self) -> io::Result<Vec<Key>> {
+        Ok(api::get::<Json, KeyList>(self, "key/list", &[])?.keys.unwrap_or_else(Vec::new))
+    }
+
+    /// Generate a new key with the given name. See [key::gen_key](key/fn.gen_key.html).
+    pub fn gen_key(&self, name: &str, key_type: KeyType) -> io::Result<Key> {
+        Ok(match key_type {
+            KeyType::Rsa(bits) => {
+                api::post::<Json, Key>(self, "key/gen", &[
+                    ("type", "rsa"),
+                    ("size", &bits.to_string()),
+                    ("arg", name),
+                ])?
+            }
+            KeyType::Ed25519 => api::post::<Json, Key>(self, "key/gen", &[("type", "ed25519"), ("arg", name)])?,
+        })
+    }
+
+    /// Rename a key. See [key::rename_key](key/fn.rename_key.html).
+    pub fn rename_key(&self, old: &str, new: &str, force: bool) -> io::Result<(String, String)> {
+        #[derive(Deserialize)]
+        struct RenameResult {
+            #[serde(rename="Was")]
+            was: String,
+            #[serde(rename="Now")]
+            now: String,
+        }
+
+        let resp = api::post::<Json, RenameResult>(self, "key/rename", &[
+            ("force", api::bool_to_str(force)),
+            ("arg", old),
+            ("arg", new),
+        ])?;
+        Ok((resp.was, resp.now))
+    }
+
+    /// Remove a key. See [key::rm_key](key/fn.rm_key.html).
+    pub fn rm_key(&self, name: &str) -> io::Result<Vec<Key>> {
+        Ok(api::post::<Json, KeyList>(self, "key/rm", &[("arg", name)])?.keys.unwrap_or_else(Vec::new))
+    }
+}
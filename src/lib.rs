@@ -2,6 +2,12 @@
 //!
 //! To get an object, just call `get(object_name)` where `object_name` is an
 //! object hash, ipfs path `/ipfs/$object_hash`, or ipns path `/ipns/$object_hash`.
+//!
+//! The free functions talk to the default API endpoint (see `set_api_endpoint`).
+//! To talk to several IPFS nodes, create a `Client` for each one.
+//!
+//! All calls block the current thread. With the `async` feature enabled,
+//! `Client` also has `*_async` variants of the core calls that return futures.
 
 #![feature(custom_derive, plugin, question_mark, associated_consts)]
 #![plugin(serde_macros)]
@@ -13,18 +19,65 @@ extern crate hyper;
 extern crate protobuf;
 extern crate url;
 extern crate rust_base58 as base58;
+extern crate crypto;
+extern crate rustc_serialize;
 extern crate multipart;
 
 #[macro_use]
 extern crate lazy_static;
 
+#[cfg(feature = "async")]
+extern crate futures;
+#[cfg(feature = "async")]
+extern crate futures_cpupool;
+
 #[allow(non_snake_case)]
 mod merkledag;
+#[allow(non_snake_case)]
+mod unixfs_pb;
 
 pub mod object;
 pub mod name;
+pub mod cat;
+pub mod add;
+pub mod pin;
+pub mod key;
+pub mod refs;
+pub mod repo;
+pub mod block;
+pub mod dag;
+pub mod swarm;
+pub mod unixfs;
+pub mod files;
+pub mod version;
+pub mod dht;
+pub mod pubsub;
+pub mod bitswap;
+pub mod transport;
+pub mod config;
+pub mod stats;
+pub mod multihash;
+pub mod log;
+pub mod bootstrap;
+pub mod path;
+pub mod diag;
+pub mod dir;
+pub mod tar;
+pub mod error;
 
 mod api;
 mod encoding;
+mod cid;
+#[cfg(unix)]
+mod unix;
+#[cfg(feature = "async")]
+mod async_api;
 
-pub use api::{set_api_endpoint, get_api_endpoint};
+pub use api::{Client, RetryPolicy, Auth};
+pub use api::{set_api_endpoint, get_api_endpoint, set_connector, set_unix_socket};
+pub use api::{set_timeout, get_timeout, set_retry_policy, set_auth, set_keep_alive_uploads};
+pub use api::{set_user_agent, DEFAULT_USER_AGENT, set_gateway_fallback};
+pub use api::{set_resolve_cache_ttl, clear_resolve_cache};
+pub use error::Error;
+pub use cat::cat;
+pub use add::{add, add_reader};
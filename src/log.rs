@@ -0,0 +1,100 @@
+//! API for accessing the IPFS node's logs.
+use std::io;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use api::{self, Client};
+use encoding::{Ignore, JsonStream};
+
+/// A log level.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum LogLevel {
+    /// Debug messages and above.
+    Debug,
+    /// Informational messages and above.
+    Info,
+    /// Warnings and above.
+    Warn,
+    /// Errors only.
+    Error,
+}
+
+impl LogLevel {
+    /// The level's name as understood by the IPFS API.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warning",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+/// Set the log level of a logging subsystem (or `"all"` for every
+/// subsystem).
+pub fn set_log_level(subsystem: &str, level: LogLevel) -> io::Result<()> {
+    api::default_client().set_log_level(subsystem, level)
+}
+
+/// Stream the IPFS node's log.
+///
+/// The returned iterator yields log events as they're logged and only ends
+/// when the connection is closed. Like [pubsub::subscribe](../pubsub/fn.subscribe.html),
+/// don't set a timeout on the client if the node may be quiet for long periods
+/// of time.
+pub fn tail() -> io::Result<LogTail> {
+    api::default_client().log_tail()
+}
+
+/// A stream of log events.
+///
+/// Returned from [tail](fn.tail.html). Each event is a JSON object; its fields
+/// depend on the IPFS node's version. If the stream is malformed, the iterator
+/// yields an error and then stops.
+pub struct LogTail {
+    events: JsonStream<Value>,
+}
+
+impl LogTail {
+    /// Give up waiting for the next event after `timeout` (default: wait
+    /// forever), yielding an `io::ErrorKind::TimedOut` error instead.
+    ///
+    /// The iterator can still be used after timing out, so this can be used to
+    /// periodically check whether to stop listening. Unlike the client's
+    /// timeout, this only applies to waiting for events, not to the initial
+    /// request.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.events.set_read_timeout(timeout);
+    }
+
+    /// Get the read timeout.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.events.read_timeout()
+    }
+}
+
+impl Iterator for LogTail {
+    type Item = io::Result<Value>;
+
+    #[inline]
+    fn next(&mut self) -> Option<io::Result<Value>> {
+        self.events.next()
+    }
+}
+
+impl Client {
+    /// Set the log level of a logging subsystem. See
+    /// [log::set_log_level](log/fn.set_log_level.html).
+    pub fn set_log_level(&self, subsystem: &str, level: LogLevel) -> io::Result<()> {
+        Ok(api::post::<Ignore, ()>(self, "log/level", &[("arg", subsystem), ("arg", level.as_str())])?)
+    }
+
+    /// Stream the IPFS node's log. See [log::tail](log/fn.tail.html).
+    pub fn log_tail(&self) -> io::Result<LogTail> {
+        Ok(LogTail {
+            events: JsonStream::new(api::get_raw(self, "log/tail", &[])?),
+        })
+    }
+}
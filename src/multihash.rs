@@ -0,0 +1,138 @@
+//! Parsing and encoding of multihashes.
+//!
+//! IPFS hashes are self-describing: each one records the hash function used
+//! and the digest length along with the digest itself. Use [decode](fn.decode.html)
+//! to validate a hash before passing it to the IPFS node.
+use std::io;
+use std::fmt;
+use std::error::Error as StdError;
+
+use base58::ToBase58;
+
+use cid::{self, Cid, Version};
+
+/// The multihash code of sha1.
+pub const SHA1: u64 = 0x11;
+/// The multihash code of sha2-256 (the default hash function).
+pub const SHA2_256: u64 = 0x12;
+/// The multihash code of sha2-512.
+pub const SHA2_512: u64 = 0x13;
+/// The multihash code of sha3-512.
+pub const SHA3_512: u64 = 0x14;
+/// The multihash code of blake2b-256.
+pub const BLAKE2B_256: u64 = 0xb220;
+/// The multihash code of blake2b-512.
+pub const BLAKE2B_512: u64 = 0xb240;
+
+/// A decoded multihash.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Multihash {
+    /// The hash function's multihash code (e.g. [SHA2_256](constant.SHA2_256.html)).
+    pub code: u64,
+    /// The digest.
+    pub digest: Vec<u8>,
+}
+
+/// An error encountered while decoding a multihash or CID.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CidError {
+    /// The string isn't valid in its base encoding.
+    InvalidEncoding,
+    /// The CID uses an unsupported multibase.
+    UnsupportedMultibase,
+    /// The CID uses an unsupported version.
+    UnsupportedVersion,
+    /// A varint was malformed or truncated.
+    InvalidVarint,
+    /// The digest is missing or doesn't match the multihash's declared length.
+    InvalidLength,
+}
+
+impl fmt::Display for CidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl StdError for CidError {
+    fn description(&self) -> &str {
+        match *self {
+            CidError::InvalidEncoding => "invalid hash encoding",
+            CidError::UnsupportedMultibase => "unsupported multibase",
+            CidError::UnsupportedVersion => "unsupported cid version",
+            CidError::InvalidVarint => "invalid varint",
+            CidError::InvalidLength => "invalid digest length",
+        }
+    }
+}
+
+impl From<CidError> for io::Error {
+    fn from(e: CidError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+impl Multihash {
+    /// Create a multihash from a multihash code and a digest.
+    pub fn new(code: u64, digest: Vec<u8>) -> Multihash {
+        Multihash {
+            code: code,
+            digest: digest,
+        }
+    }
+
+    /// Decode a multihash from its binary representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Multihash, CidError> {
+        let mut rest = bytes;
+        let code = cid::read_varint(&mut rest)?;
+        let len = cid::read_varint(&mut rest)?;
+        if len == 0 || len != rest.len() as u64 {
+            return Err(CidError::InvalidLength);
+        }
+        Ok(Multihash::new(code, rest.to_owned()))
+    }
+
+    /// Encode this multihash into its binary representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.digest.len() + 4);
+        cid::write_varint(&mut bytes, self.code);
+        cid::write_varint(&mut bytes, self.digest.len() as u64);
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+}
+
+/// Decode the multihash of an IPFS hash.
+///
+/// Both legacy (`Qm...`) hashes and version 1 CIDs are accepted.
+pub fn decode(s: &str) -> Result<Multihash, CidError> {
+    Cid::parse(s).map(|cid| cid.multihash)
+}
+
+/// Encode a multihash as a legacy (base58) IPFS hash.
+pub fn encode(multihash: &Multihash) -> String {
+    multihash.to_bytes().to_base58()
+}
+
+/// Convert a CID to the given CID version (0 or 1) without asking the IPFS
+/// node.
+///
+/// Version 1 CIDs are encoded in base32 (`bafy...`). Only sha2-256 hashed
+/// merkledag objects can be represented as version 0 CIDs; converting anything
+/// else to version 0 fails with an `io::ErrorKind::InvalidInput` error.
+pub fn convert_cid(cid: &str, to_version: u8) -> io::Result<String> {
+    let mut parsed = Cid::parse(cid)?;
+    parsed.version = match to_version {
+        0 => {
+            if parsed.codec != cid::DAG_PROTOBUF || parsed.multihash.code != SHA2_256 ||
+               parsed.multihash.digest.len() != 32 {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          format!("{} can't be represented as a version 0 cid", cid)));
+            }
+            Version::V0
+        }
+        1 => Version::V1,
+        _ => return Err(CidError::UnsupportedVersion.into()),
+    };
+    Ok(parsed.to_string())
+}
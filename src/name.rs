@@ -2,54 +2,360 @@
 use std::io;
 use std::time::Duration;
 
-use api;
+use api::{self, Client};
 use object::Reference;
-use encoding::{Ignore, Json};
+use version::VersionNumber;
+use cid::Cid;
+use path::{IpfsPath, Namespace};
+use encoding::Json;
 
 /// Resolve an IPFS path.
 ///
 /// You probably don't ever need to use this function. Just use `lookup`.
 pub fn resolve(path: &str, recursive: bool) -> io::Result<String> {
-    #[derive(Deserialize)]
-    struct ResolveResult {
-        #[serde(rename="Path")]
-        path: String,
+    api::default_client().resolve(path, recursive)
+}
+
+/// Resolve the root of an IPFS path, returning a reference to the root object
+/// and the rest of the path relative to it.
+///
+/// For example, if `/ipns/example.com` points at `/ipfs/Qm.../site`,
+/// `resolve_split("/ipns/example.com/docs/readme.md")` returns a reference to
+/// `Qm...` and `"site/docs/readme.md"`. The suffix is empty if the path
+/// resolves to the root itself; otherwise, it can be passed to
+/// [Object::get](../object/struct.Object.html#method.get).
+pub fn resolve_split(path: &str) -> io::Result<(Reference, String)> {
+    api::default_client().resolve_split(path)
+}
+
+/// Resolve a DNSLink domain (e.g. `ipfs.io`) to an `/ipfs/` path.
+///
+/// DNSLink records that point at other DNSLink records (or IPNS names) are
+/// followed until they land on an `/ipfs/` path. The domain may be given with
+/// or without the `/ipns/` prefix.
+pub fn resolve_dnslink(domain: &str) -> io::Result<String> {
+    api::default_client().resolve_dnslink(domain)
+}
+
+/// Options for [resolve_name](fn.resolve_name.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct NameResolveOptions {
+    /// Resolve until the result is not an IPNS name.
+    pub recursive: bool,
+    /// Bypass the IPNS cache (useful when resolving freshly published names).
+    pub nocache: bool,
+    /// How long to search the DHT for records.
+    pub timeout: Option<Duration>,
+}
+
+/// Resolve an IPNS name using the `name/resolve` endpoint.
+pub fn resolve_name(name: &str, opts: NameResolveOptions) -> io::Result<String> {
+    api::default_client().resolve_name(name, opts)
+}
+
+/// The result of publishing an IPNS record.
+///
+/// Returned from [publish](fn.publish.html) and friends.
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct PublishResult {
+    /// The name the record was published under (the ID of the key used).
+    #[serde(rename="Name")]
+    pub name: String,
+    /// The published path (e.g. `/ipfs/Qm...`).
+    #[serde(rename="Value")]
+    pub value: String,
+}
+
+/// The multibase used to encode IPNS names (see
+/// [PublishOptions](struct.PublishOptions.html)).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum IpnsBase {
+    /// Base36 CIDs (`k51...`), the default in recent IPFS nodes.
+    Base36,
+    /// Base32 CIDs (`bafz...`).
+    Base32,
+    /// Legacy base58 peer IDs (`Qm...` or `12D3...`).
+    Base58,
+}
+
+impl IpnsBase {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            IpnsBase::Base36 => "base36",
+            IpnsBase::Base32 => "base32",
+            IpnsBase::Base58 => "base58btc",
+        }
     }
+}
 
-    let resp = api::get::<Json, ResolveResult>("resolve", &[("recursive", api::bool_to_str(recursive)), ("arg", path)])?;
-    Ok(resp.path)
+/// Options for [publish_with](fn.publish_with.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct PublishOptions<'a> {
+    /// How long the record is valid for (default: 24h).
+    pub lifetime: Option<Duration>,
+    /// The key to publish under (default: the node's own key).
+    ///
+    /// Publishing fails on IPFS nodes older than 0.4.11, which don't support
+    /// named keys.
+    pub key: Option<&'a str>,
+    /// The encoding of the returned name (default: the IPFS node's default,
+    /// which differs between versions).
+    ///
+    /// Pass the name in the same encoding to [resolve_name](fn.resolve_name.html).
+    pub ipns_base: Option<IpnsBase>,
+    /// Whether to produce a record that's readable by IPFS nodes that only
+    /// understand V1 IPNS records (default: the IPFS node's default). If
+    /// false, a V2-only record is published.
+    pub v1_compat: Option<bool>,
+}
+
+/// Publish the specified object with the given options.
+///
+/// Nodes that don't support the `ipns_base` or `v1_compat` options ignore
+/// them.
+pub fn publish_with<R: AsRef<Reference>>(obj: &R, options: &PublishOptions) -> io::Result<PublishResult> {
+    api::default_client().publish_with(obj, options)
 }
 
 /// Publish the specified object at this peer's primary address for the default
 /// duration (24h).
 ///
 /// TODO: Better explain timeouts?
-pub fn publish<R: AsRef<Reference>>(obj: &R) -> io::Result<()> {
-    publish_for(obj, Duration::from_secs(60*60)*24)
+pub fn publish<R: AsRef<Reference>>(obj: &R) -> io::Result<PublishResult> {
+    api::default_client().publish(obj)
 }
 
 /// Publish the specified object at this peer's primary address for the
 /// specified duration.
-pub fn publish_for<R: AsRef<Reference>>(obj: &R, expires_in: Duration) -> io::Result<()> {
-    let time = format!("{}s{}ns", expires_in.as_secs(), expires_in.subsec_nanos());
-    api::post::<Ignore, ()>("name/publish", &[
-        ("resolve", "false"),
-        ("lifetime", &time),
-        ("arg", obj.as_ref().hash()),
-    ])
-}
-
-// IPNS address.
-// pub struct Identity(String);
-//
-// impl Identity {
-//     pub fn publish<R: AsRef<Reference>>(&self, obj: &R) -> io::Result<()> {
-//         // FIXME: Waiting for multiple keys.
-//         publish(obj)
-//     }
-//
-//     pub fn publish_for<R: AsRef<Reference>>(&self, obj: &R, expires_in: Duration) -> io::Result<()> {
-//         // FIXME: Waiting for multiple keys.
-//         publish_for(obj, expires_in)
-//     }
-// }
+pub fn publish_for<R: AsRef<Reference>>(obj: &R, expires_in: Duration) -> io::Result<PublishResult> {
+    api::default_client().publish_for(obj, expires_in)
+}
+
+/// Publish the specified object under the named key (see
+/// [key](../key/index.html)) for the specified duration.
+///
+/// This requires go-ipfs 0.4.11 or later.
+pub fn publish_with_key<R: AsRef<Reference>>(obj: &R, key: &str, expires_in: Duration) -> io::Result<PublishResult> {
+    api::default_client().publish_with_key(obj, key, expires_in)
+}
+
+/// Check whether IPNS-over-pubsub is enabled on the IPFS node.
+///
+/// IPNS-over-pubsub is experimental and must be enabled when starting the
+/// node (`ipfs daemon --enable-namesys-pubsub`).
+pub fn name_pubsub_state() -> io::Result<bool> {
+    api::default_client().name_pubsub_state()
+}
+
+/// List the IPNS names the node is subscribed to over pubsub.
+pub fn name_pubsub_subs() -> io::Result<Vec<String>> {
+    api::default_client().name_pubsub_subs()
+}
+
+/// Cancel the node's pubsub subscription to an IPNS name.
+///
+/// Returns false if the node wasn't subscribed to the name.
+pub fn name_pubsub_cancel(name: &str) -> io::Result<bool> {
+    api::default_client().name_pubsub_cancel(name)
+}
+
+/// A peer's identity.
+///
+/// Returned from [id](fn.id.html).
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Identity {
+    /// The peer's ID (the name under which it publishes by default).
+    #[serde(rename="ID")]
+    pub id: String,
+
+    /// The peer's public key (base64 encoded).
+    #[serde(rename="PublicKey")]
+    pub public_key: String,
+
+    /// The peer's addresses.
+    #[serde(rename="Addresses", default)]
+    pub addresses: Option<Vec<String>>,
+
+    /// The peer's agent version.
+    #[serde(rename="AgentVersion")]
+    pub agent_version: String,
+
+    /// The peer's protocol version.
+    #[serde(rename="ProtocolVersion")]
+    pub protocol_version: String,
+}
+
+/// Get this node's identity.
+pub fn id() -> io::Result<Identity> {
+    api::default_client().id()
+}
+
+/// Get a remote peer's identity.
+pub fn id_of(peer: &str) -> io::Result<Identity> {
+    api::default_client().id_of(peer)
+}
+
+#[derive(Deserialize)]
+struct ResolveResult {
+    #[serde(rename="Path")]
+    path: String,
+}
+
+impl Client {
+    /// Get this node's identity. See [name::id](name/fn.id.html).
+    pub fn id(&self) -> io::Result<Identity> {
+        Ok(api::get::<Json, Identity>(self, "id", &[])?)
+    }
+
+    /// Get a remote peer's identity. See [name::id_of](name/fn.id_of.html).
+    pub fn id_of(&self, peer: &str) -> io::Result<Identity> {
+        Ok(api::get::<Json, Identity>(self, "id", &[("arg", peer)])?)
+    }
+
+    /// Resolve an IPFS path. See [name::resolve](name/fn.resolve.html).
+    pub fn resolve(&self, path: &str, recursive: bool) -> io::Result<String> {
+        let resp = api::get::<Json, ResolveResult>(self, "resolve", &[("recursive", api::bool_to_str(recursive)), ("arg", path)])?;
+        Ok(resp.path)
+    }
+
+    /// Resolve the root of an IPFS path. See
+    /// [name::resolve_split](name/fn.resolve_split.html).
+    pub fn resolve_split(&self, path: &str) -> io::Result<(Reference, String)> {
+        let path = IpfsPath::parse(path)?;
+        let (root, suffix) = match path.namespace() {
+            Namespace::Ipfs => (path.clone(), None),
+            Namespace::Ipns => {
+                let resolved = self.resolve(&format!("/ipns/{}", path.root()), true)?;
+                let resolved = IpfsPath::parse(&resolved)?;
+                if resolved.namespace() != Namespace::Ipfs {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                              format!("{} didn't resolve to an ipfs path", path)));
+                }
+                (resolved, path.remainder())
+            }
+        };
+        let suffix = match (root.remainder(), suffix) {
+            (Some(a), Some(b)) => format!("{}/{}", a, b),
+            (Some(a), None) => a.to_owned(),
+            (None, Some(b)) => b.to_owned(),
+            (None, None) => String::new(),
+        };
+        Ok((self.lookup(root.root())?, suffix))
+    }
+
+    /// Resolve a DNSLink domain. See
+    /// [name::resolve_dnslink](name/fn.resolve_dnslink.html).
+    pub fn resolve_dnslink(&self, domain: &str) -> io::Result<String> {
+        let domain = domain.trim_left_matches("/ipns/").trim_right_matches('/');
+        if domain.is_empty() || domain.contains('/') {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid domain"));
+        }
+        let path = self.resolve(&format!("/ipns/{}", domain), true)?;
+        let valid = path.starts_with("/ipfs/") &&
+                    path[6..].split('/').next().map_or(false, |c| Cid::parse(c).is_ok());
+        if !valid {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                      format!("{} didn't resolve to an ipfs path: {}", domain, path)));
+        }
+        Ok(path)
+    }
+
+    /// Resolve an IPNS name. See [name::resolve_name](name/fn.resolve_name.html).
+    pub fn resolve_name(&self, name: &str, opts: NameResolveOptions) -> io::Result<String> {
+        let timeout = opts.timeout.map(api::duration_to_str);
+        let mut args = vec![
+            ("recursive", api::bool_to_str(opts.recursive)),
+            ("nocache", api::bool_to_str(opts.nocache)),
+        ];
+        if let Some(ref timeout) = timeout {
+            args.push(("dht-timeout", &timeout[..]));
+        }
+        args.push(("arg", name));
+
+        let resp = api::get::<Json, ResolveResult>(self, "name/resolve", &args)?;
+        Ok(resp.path)
+    }
+
+    /// Publish an object. See [name::publish](name/fn.publish.html).
+    pub fn publish<R: AsRef<Reference>>(&self, obj: &R) -> io::Result<PublishResult> {
+        self.publish_for(obj, Duration::from_secs(60*60)*24)
+    }
+
+    /// Publish an object for the specified duration. See
+    /// [name::publish_for](name/fn.publish_for.html).
+    pub fn publish_for<R: AsRef<Reference>>(&self, obj: &R, expires_in: Duration) -> io::Result<PublishResult> {
+        self.publish_with(obj, &PublishOptions {
+            lifetime: Some(expires_in),
+            ..Default::default()
+        })
+    }
+
+    /// Publish an object with the given options. See
+    /// [name::publish_with](name/fn.publish_with.html).
+    pub fn publish_with<R: AsRef<Reference>>(&self, obj: &R, options: &PublishOptions) -> io::Result<PublishResult> {
+        let time = api::duration_to_str(options.lifetime.unwrap_or(Duration::from_secs(60*60)*24));
+        let mut args = vec![("resolve", "false"), ("lifetime", &time[..])];
+        if let Some(key) = options.key {
+            // Older nodes silently ignore the key and publish under the node's
+            // own key.
+            if api::node_version(self)? < VersionNumber::new(0, 4, 11) {
+                return Err(io::Error::new(io::ErrorKind::Other, "the IPFS node doesn't support named keys"));
+            }
+            args.push(("key", key));
+        }
+        if let Some(base) = options.ipns_base {
+            args.push(("ipns-base", base.as_str()));
+        }
+        if let Some(v1_compat) = options.v1_compat {
+            args.push(("v1compat", api::bool_to_str(v1_compat)));
+        }
+        args.push(("arg", obj.as_ref().hash()));
+        Ok(api::post::<Json, PublishResult>(self, "name/publish", &args)?)
+    }
+
+    /// Publish an object under the named key. See
+    /// [name::publish_with_key](name/fn.publish_with_key.html).
+    pub fn publish_with_key<R: AsRef<Reference>>(&self, obj: &R, key: &str, expires_in: Duration) -> io::Result<PublishResult> {
+        self.publish_with(obj, &PublishOptions {
+            lifetime: Some(expires_in),
+            key: Some(key),
+            ..Default::default()
+        })
+    }
+
+    /// Check whether IPNS-over-pubsub is enabled. See
+    /// [name::name_pubsub_state](name/fn.name_pubsub_state.html).
+    pub fn name_pubsub_state(&self) -> io::Result<bool> {
+        #[derive(Deserialize)]
+        struct StateResult {
+            #[serde(rename="Enabled")]
+            enabled: bool,
+        }
+
+        Ok(api::get::<Json, StateResult>(self, "name/pubsub/state", &[])?.enabled)
+    }
+
+    /// List the IPNS names subscribed to over pubsub. See
+    /// [name::name_pubsub_subs](name/fn.name_pubsub_subs.html).
+    pub fn name_pubsub_subs(&self) -> io::Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct SubsResult {
+            #[serde(rename="Strings", default)]
+            strings: Option<Vec<String>>,
+        }
+
+        Ok(api::get::<Json, SubsResult>(self, "name/pubsub/subs", &[])?.strings.unwrap_or_else(Vec::new))
+    }
+
+    /// Cancel a pubsub subscription to an IPNS name. See
+    /// [name::name_pubsub_cancel](name/fn.name_pubsub_cancel.html).
+    pub fn name_pubsub_cancel(&self, name: &str) -> io::Result<bool> {
+        #[derive(Deserialize)]
+        struct CancelResult {
+            #[serde(rename="Canceled")]
+            canceled: bool,
+        }
+
+        Ok(api::post::<Json, CancelResult>(self, "name/pubsub/cancel", &[("arg", name)])?.canceled)
+    }
+}
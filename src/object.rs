@@ -1,15 +1,33 @@
 //! IPFS API for working with objects.
 use std::ops::Deref;
-use std::io;
+use std::io::{self, Read, BufReader};
 use std::fmt;
 use std::error::Error as StdError;
+use std::cmp;
+use std::str;
+use std::slice;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 
-use base58::{ToBase58, FromBase58};
-use protobuf::{MessageStatic, Message};
+use protobuf::{MessageStatic, Message, CodedInputStream};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use rustc_serialize::base64::{ToBase64, STANDARD};
+use serde_json;
 
-use api;
+use api::{self, Client};
 use merkledag;
-use encoding::{Json, Protobuf, Ignore};
+use unixfs_pb;
+use unixfs::UnixFsType;
+use cid::Cid;
+use multihash;
+use path::{IntoIpfsPath, IpfsPath, Namespace};
+use pin::PinType;
+use encoding::{Encoding, Json, Protobuf, Ignore};
+use error::Error;
+use version::VersionNumber;
 
 /// An IPFS object.
 #[derive(Eq, PartialEq, Default, Debug, Clone)]
@@ -28,6 +46,8 @@ pub struct Object {
 pub struct CommittedObject {
     reference: Reference,
     object: Object,
+    // The size of the object's encoded block.
+    block_size: u64,
 }
 
 impl PartialEq<CommittedObject> for CommittedObject {
@@ -67,7 +87,10 @@ impl Object {
     ///
     /// # Behavior
     ///
-    /// * This method returns the first link with the given name.
+    /// * This method returns the first link with the given name (objects may
+    ///   have several links with the same name; use
+    ///   [links_named](struct.Object.html#method.links_named) to see all of
+    ///   them).
     /// * Except empty paths (you can't look up "").
     /// * Except links with forward slashes ('/') in them. That is, "a/b/c"
     ///   resolves to `self.links["a"].links["b"].links["c"]` (pseudocode).
@@ -79,7 +102,7 @@ impl Object {
         if path.starts_with("/") {
             return Err(io::Error::new(io::ErrorKind::InvalidInput, "expected relative path"));
         }
-        let mut splits = path.splitn(1, '/');
+        let mut splits = path.splitn(2, '/');
         let prefix = splits.next().unwrap();
         let suffix = splits.next();
         for link in &self.links {
@@ -133,56 +156,136 @@ impl Object {
         }
     }
 
-    /// Commit this object to IPFS.
-    pub fn commit(self) -> Result<CommittedObject, CommitError> {
-        let mut node = merkledag::PBNode::new();
-        node.set_Links(self.links
-                           .iter()
-                           .map(|l| {
-                               let mut link = merkledag::PBLink::new();
-                               link.set_Name(l.name.to_owned());
-                               link.set_Hash(l.object.hash().from_base58().unwrap());
-                               link.set_Tsize(l.object.size());
-                               link
-                           })
-                           .collect());
-
-        node.set_Data(self.data);
-
-        #[derive(Deserialize, Debug)]
-        struct PutResult {
-            #[serde(rename="Hash")]
-            hash: String,
+    /// Add a link to this object.
+    ///
+    /// Fails with an `io::ErrorKind::InvalidInput` error if the name is empty
+    /// or contains a `/` as such links can't be traversed by
+    /// [get](struct.Object.html#method.get).
+    pub fn link<S: Into<String>>(&mut self, name: S, target: Reference) -> io::Result<()> {
+        let name = name.into();
+        if name.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty link name"));
+        }
+        if name.contains('/') {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "link names can't contain '/'"));
         }
+        self.links.push(Link {
+            name: name,
+            object: target,
+        });
+        Ok(())
+    }
 
-        let encoded = &node.write_to_bytes().unwrap()[..];
-        // TODO: To unwrap or not to unwrap?
-        let hash = match api::post_data::<Json, PutResult>("object/put", &[("inputenc", "protobuf")], encoded) {
-            Ok(PutResult { hash, .. } ) => hash,
-            Err(e) => {
-                let data = node.take_Data();
-                return Err(CommitError {
-                    error: e,
-                    object: Object {
-                        links: self.links,
-                        data: data,
-                    },
-                });
-            }
-        };
+    /// Add a link to this object, builder style. See
+    /// [link](struct.Object.html#method.link).
+    pub fn with_link<S: Into<String>>(mut self, name: S, target: Reference) -> io::Result<Object> {
+        self.link(name, target)?;
+        Ok(self)
+    }
 
-        let data = node.take_Data();
-        let object = Object {
-            links: self.links,
-            data: data,
+    /// Find the first link pointing at the object with the given hash.
+    pub fn link_by_hash(&self, hash: &str) -> Option<&Link> {
+        self.links.iter().find(|l| l.object.hash() == hash)
+    }
+
+    /// Iterate over all links with the given name, in order.
+    ///
+    /// Link names needn't be unique so, unlike [get](struct.Object.html#method.get),
+    /// this doesn't just pick the first match.
+    pub fn links_named<'a, 'b>(&'a self, name: &'b str) -> LinksNamed<'a, 'b> {
+        LinksNamed {
+            links: self.links.iter(),
+            name: name,
+        }
+    }
+
+    /// Get the object's data as a string (if it's valid UTF-8).
+    pub fn data_as_str(&self) -> Option<&str> {
+        str::from_utf8(&self.data).ok()
+    }
+
+    /// Get the type of this object if it's a UnixFS node.
+    pub fn unixfs_type(&self) -> Option<UnixFsType> {
+        self.unixfs().ok().map(|d| d.get_Type().into())
+    }
+
+    /// Check whether this is a UnixFS directory (including sharded
+    /// directories).
+    pub fn is_directory(&self) -> bool {
+        match self.unixfs_type() {
+            Some(UnixFsType::Directory) | Some(UnixFsType::HamtShard) => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether this is a UnixFS file (or a piece of one).
+    pub fn is_file(&self) -> bool {
+        match self.unixfs_type() {
+            Some(UnixFsType::File) | Some(UnixFsType::Raw) => true,
+            _ => false,
+        }
+    }
+
+    /// Get the size of the file if this is a UnixFS file.
+    ///
+    /// This is the size of the file's contents, not of the object (see
+    /// [CommittedObject::size](struct.CommittedObject.html#method.size)).
+    pub fn file_size(&self) -> Option<u64> {
+        let data = match self.unixfs() {
+            Ok(data) => data,
+            Err(_) => return None,
         };
-        Ok(CommittedObject {
-            reference: Reference {
-                size: object.links.iter().fold(encoded.len() as u64, |s, l| s + l.object.size()),
-                hash: hash,
-            },
-            object: object,
-        })
+        match UnixFsType::from(data.get_Type()) {
+            UnixFsType::File | UnixFsType::Raw if data.has_filesize() => Some(data.get_filesize()),
+            UnixFsType::File | UnixFsType::Raw => Some(data.get_Data().len() as u64),
+            _ => None,
+        }
+    }
+
+    /// Get the data stored in this UnixFS node (e.g. the file contents for a
+    /// small file).
+    ///
+    /// Fails with an `io::ErrorKind::InvalidData` error if this isn't a UnixFS
+    /// node.
+    pub fn unixfs_data(&self) -> io::Result<Vec<u8>> {
+        Ok(self.unixfs()?.take_Data())
+    }
+
+    fn unixfs(&self) -> io::Result<unixfs_pb::Data> {
+        // Don't use parse_from_bytes, it panics on missing required fields.
+        let mut data = unixfs_pb::Data::new();
+        let valid = data.merge_from(&mut CodedInputStream::from_bytes(&self.data)).is_ok() &&
+                    data.is_initialized();
+        if !valid {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a unixfs node"));
+        }
+        Ok(data)
+    }
+
+    /// Decode an object from its raw (merkledag protobuf) encoding, e.g. a block
+    /// returned by [get_block](../block/fn.get_block.html).
+    ///
+    /// This is the inverse of the encoding used by
+    /// [commit](#method.commit) and doesn't talk to the IPFS node.
+    pub fn from_protobuf(bytes: &[u8]) -> io::Result<Object> {
+        let mut node = merkledag::PBNode::new();
+        node.merge_from(&mut CodedInputStream::from_bytes(bytes))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        object_from_node(node)
+    }
+
+    /// Commit this object to IPFS.
+    pub fn commit(self) -> Result<CommittedObject, CommitError> {
+        api::default_client().commit(self)
+    }
+
+    /// Commit this object to IPFS and check that the hash returned by the IPFS
+    /// node matches the locally computed hash.
+    ///
+    /// Only sha2-256 (the default hash function) is verified; if the node
+    /// hashed the object with some other hash function, the check is skipped.
+    pub fn commit_verified(self) -> Result<CommittedObject, CommitError> {
+        api::default_client().commit_verified(self)
     }
 }
 
@@ -197,6 +300,30 @@ impl CommittedObject {
         self.reference.pin(recursive)
     }
 
+    /// Pin this object with a name. See
+    /// [Reference::pin_named](struct.Reference.html#method.pin_named).
+    pub fn pin_named(&self, recursive: bool, name: &str) -> io::Result<()> {
+        self.reference.pin_named(recursive, name)
+    }
+
+    /// Pin this object, succeeding if it's already pinned. See
+    /// [Reference::ensure_pinned](struct.Reference.html#method.ensure_pinned).
+    pub fn ensure_pinned(&self, recursive: bool) -> io::Result<()> {
+        self.reference.ensure_pinned(recursive)
+    }
+
+    /// Unpin this object, succeeding if it isn't pinned. See
+    /// [Reference::ensure_unpinned](struct.Reference.html#method.ensure_unpinned).
+    pub fn ensure_unpinned(&self, recursive: bool) -> io::Result<()> {
+        self.reference.ensure_unpinned(recursive)
+    }
+
+    /// Check whether this object is pinned. See
+    /// [Reference::is_pinned](struct.Reference.html#method.is_pinned).
+    pub fn is_pinned(&self) -> io::Result<Option<PinType>> {
+        self.reference.is_pinned()
+    }
+
     /// Stat this object.
     ///
     /// Note: This method does not make any network calls.
@@ -206,6 +333,8 @@ impl CommittedObject {
             num_links: self.links.len() as u32,
             data_size: self.data.len() as u32,
             cumulative_size: self.size(),
+            block_size: self.block_size,
+            links_size: self.block_size - self.data.len() as u64,
             _non_exhaustive: (),
         }
     }
@@ -223,6 +352,10 @@ impl CommittedObject {
     }
 
     /// Get the (precomputed) size of the object
+    ///
+    /// The cumulative size is computed once, when the object is committed,
+    /// from the sizes recorded in its links' references, so this never walks
+    /// the DAG.
     #[inline]
     pub fn size(&self) -> u64 {
         self.reference.size()
@@ -233,6 +366,53 @@ impl CommittedObject {
     pub fn edit(self) -> Object {
         self.object
     }
+
+    /// Iterate over this object's children, fetching each one as the iterator
+    /// is advanced.
+    ///
+    /// Failing to fetch one child yields an error but doesn't end the
+    /// iteration.
+    pub fn children(&self) -> Children {
+        Children {
+            links: self.links.iter(),
+        }
+    }
+}
+
+/// A lazy iterator over an object's children.
+///
+/// Returned from [CommittedObject::children](struct.CommittedObject.html#method.children).
+pub struct Children<'a> {
+    links: slice::Iter<'a, Link>,
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = io::Result<CommittedObject>;
+
+    fn next(&mut self) -> Option<io::Result<CommittedObject>> {
+        self.links.next().map(|l| l.object.get())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.links.size_hint()
+    }
+}
+
+/// An iterator over the links with a given name.
+///
+/// Returned from [Object::links_named](struct.Object.html#method.links_named).
+pub struct LinksNamed<'a, 'b> {
+    links: slice::Iter<'a, Link>,
+    name: &'b str,
+}
+
+impl<'a, 'b> Iterator for LinksNamed<'a, 'b> {
+    type Item = &'a Link;
+
+    fn next(&mut self) -> Option<&'a Link> {
+        let name = self.name;
+        self.links.find(|l| l.name == name)
+    }
 }
 
 /// An IPFS link. See [Object](struct.Object.html).
@@ -248,9 +428,193 @@ pub struct Link {
 
 /// Get an object.
 ///
-/// This is a shortcut for `lookup(path)?.get()`.
-pub fn get(path: &str) -> io::Result<CommittedObject> {
-    lookup(path)?.get()
+/// The path may be a bare hash, an `/ipfs/` path, or an `/ipns/` path (see
+/// [IpfsPath](../path/struct.IpfsPath.html)). This is a shortcut for
+/// `lookup(path)?.get()`.
+pub fn get<P: IntoIpfsPath>(path: P) -> io::Result<CommittedObject> {
+    api::default_client().get(path)
+}
+
+/// Options for [get_with](fn.get_with.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct GetOptions {
+    /// Only consult the local block store, failing with an
+    /// `io::ErrorKind::NotFound` error if the object isn't present locally
+    /// instead of fetching it from the network.
+    pub offline: bool,
+    /// Refuse to get objects whose cumulative size (the size of the object and
+    /// everything it links to) exceeds this many bytes, failing with an
+    /// `io::ErrorKind::InvalidInput` error.
+    ///
+    /// This is checked before the object is fetched.
+    pub max_size: Option<u64>,
+    /// Check that the size recorded in the reference matches the object's
+    /// encoded size plus the sizes recorded in its links, failing with an
+    /// `io::ErrorKind::InvalidData` error on mismatch.
+    ///
+    /// Off by default: the recorded sizes come from the parent's links (as
+    /// reported by the IPFS node) and aren't guaranteed to be exact.
+    pub verify_size: bool,
+}
+
+/// Get an object with the specified options.
+pub fn get_with<P: IntoIpfsPath>(path: P, options: &GetOptions) -> io::Result<CommittedObject> {
+    api::default_client().get_with(path, options)
+}
+
+/// The default number of concurrent requests made by
+/// [get_many](fn.get_many.html).
+pub const DEFAULT_GET_CONCURRENCY: usize = 8;
+
+/// Get many objects at once.
+///
+/// Up to [DEFAULT_GET_CONCURRENCY](constant.DEFAULT_GET_CONCURRENCY.html)
+/// objects are fetched concurrently. The results are returned in the same order
+/// as the references and failing to fetch one object doesn't prevent the others
+/// from being fetched.
+pub fn get_many(refs: &[Reference]) -> Vec<io::Result<CommittedObject>> {
+    api::default_client().get_many(refs)
+}
+
+/// Get many objects at once, making at most `concurrency` requests at a time.
+///
+/// See [get_many](fn.get_many.html).
+pub fn get_many_with(refs: &[Reference], concurrency: usize) -> Vec<io::Result<CommittedObject>> {
+    api::default_client().get_many_with(refs, concurrency)
+}
+
+/// Stat many objects at once.
+///
+/// Like [get_many](fn.get_many.html), up to
+/// [DEFAULT_GET_CONCURRENCY](constant.DEFAULT_GET_CONCURRENCY.html) requests
+/// are made concurrently and the results are returned in the same order as the
+/// paths.
+pub fn stat_many(paths: &[&str]) -> Vec<io::Result<Stat>> {
+    api::default_client().stat_many(paths)
+}
+
+/// Stat many objects at once, making at most `concurrency` requests at a time.
+///
+/// See [stat_many](fn.stat_many.html).
+pub fn stat_many_with(paths: &[&str], concurrency: usize) -> Vec<io::Result<Stat>> {
+    api::default_client().stat_many_with(paths, concurrency)
+}
+
+/// Get an object's links without fetching its data.
+///
+/// This is much cheaper than [get](fn.get.html) for objects with large data
+/// fields.
+pub fn links(path: &str) -> io::Result<Vec<Link>> {
+    api::default_client().links(path)
+}
+
+/// Stream an object's links.
+///
+/// Unlike [get](fn.get.html) and [links](fn.links.html), this decodes the
+/// links one at a time as they're received so memory usage doesn't grow with
+/// the number of links. The object's data is skipped.
+pub fn get_links_streaming(path: &str) -> io::Result<LinkStream> {
+    api::default_client().get_links_streaming(path)
+}
+
+/// A streaming iterator over an object's links.
+///
+/// Returned from [get_links_streaming](fn.get_links_streaming.html). If the
+/// response is malformed or truncated, the iterator yields an error and then
+/// stops.
+pub struct LinkStream {
+    reader: BufReader<Box<Read + Send>>,
+    client: Client,
+    done: bool,
+}
+
+impl Iterator for LinkStream {
+    type Item = io::Result<Link>;
+
+    fn next(&mut self) -> Option<io::Result<Link>> {
+        if self.done {
+            return None;
+        }
+        match self.next_link() {
+            Ok(Some(link)) => Some(Ok(link)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl LinkStream {
+    fn next_link(&mut self) -> io::Result<Option<Link>> {
+        loop {
+            let key = match read_varint(&mut self.reader)? {
+                Some(key) => key,
+                None => return Ok(None),
+            };
+            let len = match key & 0x7 {
+                0 => {
+                    expect_varint(&mut self.reader)?;
+                    continue;
+                }
+                1 => 8,
+                2 => expect_varint(&mut self.reader)?,
+                5 => 4,
+                _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid wire type")),
+            };
+            if key >> 3 != 2 {
+                // Not a link, skip it.
+                let skipped = io::copy(&mut (&mut self.reader).take(len), &mut io::sink())?;
+                if skipped != len {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated object"));
+                }
+                continue;
+            }
+
+            // Don't trust the length with preallocating the buffer.
+            let mut buf = Vec::new();
+            let read = (&mut self.reader).take(len).read_to_end(&mut buf)?;
+            if read as u64 != len {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated object"));
+            }
+            let mut link = <Protobuf as Encoding<merkledag::PBLink>>::parse(&mut &buf[..], None)?;
+            let cid = Cid::from_bytes(link.get_Hash())?;
+            return Ok(Some(Link {
+                name: link.take_Name(),
+                object: Reference::new(cid.to_string(), link.get_Tsize()).with_client(&self.client),
+            }));
+        }
+    }
+}
+
+// Reads a varint, returning `None` on EOF.
+fn read_varint<R: Read>(r: &mut R) -> io::Result<Option<u64>> {
+    let mut value = 0u64;
+    for i in 0..10 {
+        let mut byte = [0u8];
+        if r.read(&mut byte)? == 0 {
+            if i == 0 {
+                return Ok(None);
+            }
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated object"));
+        }
+        value |= ((byte[0] & 0x7f) as u64) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "invalid varint"))
+}
+
+fn expect_varint<R: Read>(r: &mut R) -> io::Result<u64> {
+    match read_varint(r)? {
+        Some(v) => Ok(v),
+        None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated object")),
+    }
 }
 
 /// Status of an IPFS object.
@@ -274,27 +638,188 @@ pub struct Stat {
     #[serde(rename="CumulativeSize")]
     pub cumulative_size: u64,
 
+    /// The size of the object's encoded block.
+    #[serde(rename="BlockSize", default)]
+    pub block_size: u64,
+
+    /// The size of the encoded links (the block size minus the data size).
+    #[serde(rename="LinksSize", default)]
+    pub links_size: u64,
+
     #[doc(hidden)]
     #[serde(default)]
     _non_exhaustive: (),
 }
 
+// Just the cumulative size from `object/stat` (the other fields are skipped
+// while parsing).
+#[derive(Deserialize)]
+struct CumulativeSize {
+    #[serde(rename="CumulativeSize")]
+    cumulative_size: u64,
+}
+
+impl Stat {
+    /// The cumulative size formatted for humans (e.g. `1.5 MiB`).
+    pub fn human_cumulative_size(&self) -> String {
+        human_size(self.cumulative_size)
+    }
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({} links, {} data, {} total)",
+               self.hash,
+               self.num_links,
+               human_size(self.data_size as u64),
+               human_size(self.cumulative_size))
+    }
+}
+
+// Formats a size in bytes using binary (1024-based) units.
+fn human_size(size: u64) -> String {
+    const UNITS: [&'static str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if size < 1024 {
+        return format!("{} B", size);
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// The error returned when an object can't be stat'ed.
+#[derive(Debug)]
+pub enum StatError {
+    /// The path (or hash) was malformed.
+    InvalidPath(Error),
+    /// The object isn't available on the local node (only returned by
+    /// [stat_offline](fn.stat_offline.html)).
+    NotFoundLocal,
+    /// The object couldn't be found on the network before the request timed
+    /// out.
+    NotFoundNetwork,
+    /// Some other error.
+    Other(Error),
+}
+
+impl StatError {
+    fn classify(e: Error) -> StatError {
+        if let Error::Io(ref e) = e {
+            if e.kind() == io::ErrorKind::TimedOut {
+                return StatError::NotFoundNetwork;
+            }
+        }
+        if e.is_api_error(api::ipfs_error::INVALID_REF) {
+            StatError::InvalidPath(e)
+        } else if e.is_api_error(api::ipfs_error::NOT_FOUND) {
+            StatError::NotFoundLocal
+        } else if e.is_api_error(api::ipfs_error::DEADLINE_EXCEEDED) {
+            StatError::NotFoundNetwork
+        } else {
+            StatError::Other(e)
+        }
+    }
+}
+
+impl fmt::Display for StatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StatError::InvalidPath(ref e) | StatError::Other(ref e) => e.fmt(f),
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+
+impl ::std::error::Error for StatError {
+    fn description(&self) -> &str {
+        match *self {
+            StatError::InvalidPath(ref e) | StatError::Other(ref e) => e.description(),
+            StatError::NotFoundLocal => "object not found locally",
+            StatError::NotFoundNetwork => "object not found on the network",
+        }
+    }
+    fn cause(&self) -> Option<&::std::error::Error> {
+        match *self {
+            StatError::InvalidPath(ref e) | StatError::Other(ref e) => e.cause(),
+            _ => None,
+        }
+    }
+}
+
+impl From<StatError> for io::Error {
+    fn from(e: StatError) -> io::Error {
+        match e {
+            StatError::InvalidPath(e) | StatError::Other(e) => e.into(),
+            StatError::NotFoundLocal => io::Error::new(io::ErrorKind::NotFound, "object not found locally"),
+            StatError::NotFoundNetwork => io::Error::new(io::ErrorKind::TimedOut, "object not found on the network"),
+        }
+    }
+}
+
 /// Lookup information about an object.
 ///
 /// This *will* cause the IPFS node to fetch the object but won't try to
 /// materialize it (so it's faster than get, especially if the object hash been
 /// cached).
-pub fn stat(path: &str) -> io::Result<Stat> {
-    api::get::<Json, Stat>("object/stat", &[("arg", path)])
+pub fn stat<P: IntoIpfsPath>(path: P) -> Result<Stat, StatError> {
+    api::default_client().stat(path)
+}
+
+/// Lookup information about an object without touching the network.
+///
+/// Unlike [stat](fn.stat.html), this returns `StatError::NotFoundLocal`
+/// immediately if the IPFS node doesn't already have the object.
+pub fn stat_offline<P: IntoIpfsPath>(path: P) -> Result<Stat, StatError> {
+    api::default_client().stat_offline(path)
+}
+
+/// Options for [stat_with](fn.stat_with.html) and
+/// [lookup_with](fn.lookup_with.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct StatOptions {
+    /// Only consult the local block store, failing with
+    /// `StatError::NotFoundLocal` if the object isn't present locally.
+    pub offline: bool,
+}
+
+/// Lookup information about an object with the specified options.
+pub fn stat_with<P: IntoIpfsPath>(path: P, options: &StatOptions) -> Result<Stat, StatError> {
+    api::default_client().stat_with(path, options)
 }
 
 /// A thin reference to an object.
 ///
-/// Dereferences to the object's hash.
-#[derive(Eq, PartialEq, Debug, Clone)]
+/// Dereferences to the object's hash. References can be serialized (as
+/// `{"hash": ..., "size": ...}`) so that they can be persisted and later
+/// reused without looking them up again.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Reference {
     size: u64,
     hash: String,
+    // The client the reference came from (used by its methods).
+    #[serde(skip_serializing, skip_deserializing)]
+    client: Option<Client>,
+}
+
+impl PartialEq for Reference {
+    fn eq(&self, other: &Reference) -> bool {
+        self.size == other.size && self.hash == other.hash
+    }
+}
+
+impl Eq for Reference {}
+
+impl fmt::Debug for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Reference")
+            .field("size", &self.size)
+            .field("hash", &self.hash)
+            .finish()
+    }
 }
 
 impl fmt::Display for Reference {
@@ -313,32 +838,48 @@ impl Deref for Reference {
 }
 
 impl Reference {
-    /// Get the referenced object.
-    pub fn get(&self) -> io::Result<CommittedObject> {
-        let mut node = api::get::<Protobuf, merkledag::PBNode>("object/get", &[("arg", &self.hash)])?;
+    /// Construct a reference from an object's hash and cumulative size.
+    ///
+    /// Note: This doesn't check that the object exists or that the size is
+    /// correct.
+    pub fn new(hash: String, size: u64) -> Reference {
+        Reference {
+            hash: hash,
+            size: size,
+            client: None,
+        }
+    }
 
-        let links: Vec<Link> = node.take_Links()
-            .into_iter()
-            .map(|mut l| {
-                Link {
-                    name: l.take_Name(),
-                    object: Reference {
-                        size: l.get_Tsize(),
-                        hash: l.take_Hash().to_base58(),
-                    },
-                }
-            })
-            .collect();
+    /// Use the given client for this reference's methods (e.g.
+    /// [get](#method.get)).
+    ///
+    /// References returned by a [Client](../struct.Client.html) (and the links
+    /// of objects fetched through it) already use that client. Others (e.g.
+    /// deserialized references) use the default client.
+    pub fn with_client(mut self, client: &Client) -> Reference {
+        self.client = Some(client.clone());
+        self
+    }
 
-        let data = node.take_Data();
+    // The client this reference came from, if any, otherwise the default one.
+    fn client(&self) -> Client {
+        match self.client {
+            Some(ref client) => client.clone(),
+            None => api::default_client(),
+        }
+    }
 
-        Ok(CommittedObject {
-            reference: self.clone(),
-            object: Object {
-                data: data,
-                links: links,
-            }
-        })
+    /// Get the referenced object.
+    pub fn get(&self) -> io::Result<CommittedObject> {
+        self.client().fetch(self)
+    }
+
+    /// Get the referenced object with the specified options.
+    ///
+    /// The [max_size](struct.GetOptions.html#structfield.max_size) limit is
+    /// checked against this reference's size.
+    pub fn get_with(&self, options: &GetOptions) -> io::Result<CommittedObject> {
+        self.client().fetch_with(self, options)
     }
 
     /// Get the size of the referenced object.
@@ -355,21 +896,42 @@ impl Reference {
 
     /// Unpin this object.
     pub fn unpin(&self, recursive: bool) -> io::Result<()> {
-        api::post::<Ignore, ()>("pin/rm", &[("recursive", api::bool_to_str(recursive)), ("arg", &self)])
-            .or_else(|e| {
-                if e.description() == api::ipfs_error::NOT_PINNED  {
-                    // We consider this to be a success. That is, the object is
-                    // no longer pinned.
-                    return Ok(());
-                }
-                debug_assert!(e.description() != api::ipfs_error::INVALID_REF, "sent an invalid ref to the server");
-                Err(e)
-            })
+        self.client().unpin(self, recursive)
     }
 
     /// Pin this object.
     pub fn pin(&self, recursive: bool) -> io::Result<()> {
-        api::post::<Ignore, ()>("pin/add", &[("recursive", api::bool_to_str(recursive)), ("arg", &self)])
+        self.client().pin(self, recursive)
+    }
+
+    /// Pin this object with a name describing why it's pinned.
+    ///
+    /// The name is listed by [pin::pinned_with_names](../pin/fn.pinned_with_names.html).
+    /// IPFS nodes that don't support pin names pin the object without one.
+    pub fn pin_named(&self, recursive: bool, name: &str) -> io::Result<()> {
+        self.client().pin_named(self, recursive, name)
+    }
+
+    /// Pin this object, treating "already pinned" as success.
+    ///
+    /// Depending on the daemon version, pinning an object that's already
+    /// pinned (e.g. directly pinning a recursively pinned object) may fail.
+    pub fn ensure_pinned(&self, recursive: bool) -> io::Result<()> {
+        self.client().ensure_pinned(self, recursive)
+    }
+
+    /// Unpin this object, treating "not pinned" (in any of its variants) as
+    /// success.
+    pub fn ensure_unpinned(&self, recursive: bool) -> io::Result<()> {
+        self.client().ensure_unpinned(self, recursive)
+    }
+
+    /// Check whether this object is pinned.
+    ///
+    /// Returns the type of the pin (`Direct`, `Recursive`, or `Indirect` if an
+    /// ancestor is pinned recursively) or `None` if the object isn't pinned.
+    pub fn is_pinned(&self) -> io::Result<Option<PinType>> {
+        self.client().is_pinned(self)
     }
 }
 
@@ -387,10 +949,614 @@ impl AsRef<Reference> for CommittedObject {
 ///
 /// Note: This will still cause the IPFS API node to download the object into
 /// it's block store.
-pub fn lookup(path: &str) -> io::Result<Reference> {
-    let stats = stat(&path)?;
-    Ok(Reference {
-        hash: stats.hash,
-        size: stats.cumulative_size,
+pub fn lookup<P: IntoIpfsPath>(path: P) -> io::Result<Reference> {
+    api::default_client().lookup(path)
+}
+
+/// Lookup the total size of an object and its children.
+///
+/// This is cheaper than [stat](fn.stat.html) when looking up the sizes of
+/// many objects: it only parses the cumulative size and answers from the
+/// lookup cache (see [Client::set_resolve_cache_ttl](../struct.Client.html#method.set_resolve_cache_ttl))
+/// when it can.
+pub fn stat_size_only<P: IntoIpfsPath>(path: P) -> io::Result<u64> {
+    api::default_client().stat_size_only(path)
+}
+
+// Calls `f` for each index in `0..len` using at most `concurrency` worker
+// threads, collecting the results in order.
+fn run_concurrently<T, F>(client: &Client, len: usize, concurrency: usize, f: F) -> Vec<io::Result<T>>
+    where T: Send + 'static,
+          F: Fn(&Client, usize) -> io::Result<T> + Send + Sync + 'static
+{
+    let workers = cmp::max(1, cmp::min(concurrency, len));
+    let f = Arc::new(f);
+    let next = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = mpsc::channel();
+
+    for _ in 0..workers {
+        let client = client.clone();
+        let f = f.clone();
+        let next = next.clone();
+        let tx = tx.clone();
+        thread::spawn(move || {
+            loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= len || tx.send((i, f(&client, i))).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    let mut results: Vec<Option<io::Result<T>>> = (0..len).map(|_| None).collect();
+    for (i, result) in rx {
+        results[i] = Some(result);
+    }
+    results.into_iter()
+        .map(|r| r.unwrap_or_else(|| Err(io::Error::new(io::ErrorKind::Other, "worker thread panicked"))))
+        .collect()
+}
+
+// The result of `object/put`.
+#[derive(Deserialize)]
+struct PutResult {
+    #[serde(rename="Hash")]
+    hash: String,
+}
+
+// Makes the links' methods use the given client.
+fn bind_links(links: &mut [Link], client: &Client) {
+    for link in links {
+        link.object.client = Some(client.clone());
+    }
+}
+
+fn object_from_node(mut node: merkledag::PBNode) -> io::Result<Object> {
+    let links: io::Result<Vec<Link>> = node.take_Links()
+        .into_iter()
+        .map(|mut l| -> io::Result<Link> {
+            let cid = Cid::from_bytes(l.get_Hash())?;
+            Ok(Link {
+                name: l.take_Name(),
+                object: Reference::new(cid.to_string(), l.get_Tsize()),
+            })
+        })
+        .collect();
+    Ok(Object {
+        links: links?,
+        data: node.take_Data(),
     })
 }
+
+// Older IPFS nodes took `object/put`'s input encoding as an argument rather
+// than as the `inputenc` option (added in 0.4.3) and only accepted text data
+// fields in JSON objects (`datafieldenc` was added in 0.4.5).
+fn put_args(client: &Client,
+            inputenc: &'static str,
+            datafieldenc: Option<&'static str>)
+            -> io::Result<Vec<(&'static str, &'static str)>> {
+    let version = api::node_version(client)?;
+    if datafieldenc.map_or(false, |enc| enc != "text") && version < VersionNumber::new(0, 4, 5) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "the IPFS node only supports UTF-8 data in JSON objects"));
+    }
+    if version < VersionNumber::new(0, 4, 3) {
+        return Ok(vec![("arg", inputenc)]);
+    }
+    let mut args = vec![("inputenc", inputenc)];
+    if let Some(datafieldenc) = datafieldenc {
+        args.push(("datafieldenc", datafieldenc));
+    }
+    Ok(args)
+}
+
+fn encode_links(links: &[Link]) -> io::Result<Vec<merkledag::PBLink>> {
+    links.iter()
+        .map(|l| -> io::Result<merkledag::PBLink> {
+            let cid = Cid::parse(l.object.hash())?;
+            let mut link = merkledag::PBLink::new();
+            link.set_Name(l.name.to_owned());
+            link.set_Hash(cid.to_bytes());
+            link.set_Tsize(l.object.size());
+            Ok(link)
+        })
+        .collect()
+}
+
+fn verify_hash(object: &CommittedObject) -> io::Result<()> {
+    let multihash = multihash::decode(object.hash())?;
+    if multihash.code != multihash::SHA2_256 || multihash.digest.len() != 32 {
+        // Not the default hash function, don't bother.
+        return Ok(());
+    }
+
+    let mut node = merkledag::PBNode::new();
+    node.set_Links(encode_links(&object.links)?.into_iter().collect());
+    node.set_Data(object.data.clone());
+
+    let mut hasher = Sha256::new();
+    hasher.input(&node.write_to_bytes().unwrap());
+    let mut digest = [0u8; 32];
+    hasher.result(&mut digest);
+
+    if &multihash.digest[..] == &digest[..] {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidData,
+                           format!("hash mismatch: the IPFS node returned {}", object.hash())))
+    }
+}
+
+/// Get a reference to an object with the specified options.
+///
+/// With `offline` set, this is a cheap way to check whether or not an object is
+/// available locally: it fails with an `io::ErrorKind::NotFound` error instead
+/// of fetching the object from the network.
+pub fn lookup_with<P: IntoIpfsPath>(path: P, options: &StatOptions) -> io::Result<Reference> {
+    api::default_client().lookup_with(path, options)
+}
+
+/// The kind of an [ObjectChange](struct.ObjectChange.html).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ChangeType {
+    /// A link was added.
+    Add,
+    /// A link was removed.
+    Remove,
+    /// A link now points at a different object.
+    Modify,
+}
+
+/// A difference between two DAGs.
+///
+/// Returned from [diff](fn.diff.html).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ObjectChange {
+    /// The kind of change.
+    pub change_type: ChangeType,
+    /// The path (relative to the roots) of the changed link.
+    pub path: String,
+    /// The hash of the object the link pointed at before (`None` for
+    /// additions).
+    pub before: Option<String>,
+    /// The hash of the object the link points at now (`None` for removals).
+    pub after: Option<String>,
+}
+
+/// List the changes between two DAGs.
+///
+/// Only the links that differ are descended into so this is cheap for similar
+/// DAGs.
+pub fn diff(a: &str, b: &str) -> io::Result<Vec<ObjectChange>> {
+    api::default_client().diff(a, b)
+}
+
+/// Put an object into IPFS using `object/put`'s JSON input encoding.
+///
+/// Unlike [Object::commit](struct.Object.html#method.commit), the object is
+/// never encoded locally; the IPFS node builds the object from its JSON
+/// description. Data that isn't valid UTF-8 is sent base64 encoded, which
+/// IPFS nodes older than 0.4.5 don't support.
+pub fn put_object_json(data: Vec<u8>, links: Vec<Link>) -> io::Result<CommittedObject> {
+    api::default_client().put_object_json(data, links)
+}
+
+impl Client {
+    /// Commit an object to IPFS. See [Object::commit](object/struct.Object.html#method.commit).
+    pub fn commit(&self, object: Object) -> Result<CommittedObject, CommitError> {
+        let prepared = put_args(self, "protobuf", None)
+            .and_then(|args| encode_links(&object.links).map(|links| (args, links)));
+        let (args, links) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                return Err(CommitError {
+                    error: e,
+                    object: object,
+                })
+            }
+        };
+
+        let mut node = merkledag::PBNode::new();
+        node.set_Links(links.into_iter().collect());
+
+        node.set_Data(object.data);
+
+        let encoded = &node.write_to_bytes().unwrap()[..];
+        // TODO: To unwrap or not to unwrap?
+        let hash = match api::post_data::<Json, PutResult>(self, "object/put", &args, encoded) {
+            Ok(PutResult { hash, .. } ) => hash,
+            Err(e) => {
+                let data = node.take_Data();
+                return Err(CommitError {
+                    error: e.into(),
+                    object: Object {
+                        links: object.links,
+                        data: data,
+                    },
+                });
+            }
+        };
+
+        let data = node.take_Data();
+        let mut object = Object {
+            links: object.links,
+            data: data,
+        };
+        bind_links(&mut object.links, self);
+        let size = object.links.iter().fold(encoded.len() as u64, |s, l| s + l.object.size());
+        Ok(CommittedObject {
+            reference: Reference::new(hash, size).with_client(self),
+            object: object,
+            block_size: encoded.len() as u64,
+        })
+    }
+
+    /// Commit an object to IPFS, verifying the returned hash. See
+    /// [Object::commit_verified](object/struct.Object.html#method.commit_verified).
+    pub fn commit_verified(&self, object: Object) -> Result<CommittedObject, CommitError> {
+        let committed = self.commit(object)?;
+        let error = match verify_hash(&committed) {
+            Ok(()) => return Ok(committed),
+            Err(e) => e,
+        };
+        Err(CommitError {
+            error: error,
+            object: committed.edit(),
+        })
+    }
+
+    /// Put an object using the JSON input encoding. See
+    /// [object::put_object_json](object/fn.put_object_json.html).
+    pub fn put_object_json(&self, data: Vec<u8>, links: Vec<Link>) -> io::Result<CommittedObject> {
+        #[derive(Serialize)]
+        struct JsonObject<'a> {
+            #[serde(rename="Data")]
+            data: String,
+            #[serde(rename="Links")]
+            links: Vec<JsonLink<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonLink<'a> {
+            #[serde(rename="Name")]
+            name: &'a str,
+            #[serde(rename="Hash")]
+            hash: &'a str,
+            #[serde(rename="Size")]
+            size: u64,
+        }
+
+        let (encoded_data, datafieldenc) = match str::from_utf8(&data) {
+            Ok(s) => (s.to_owned(), "text"),
+            Err(_) => (data.to_base64(STANDARD), "base64"),
+        };
+        let body = serde_json::to_vec(&JsonObject {
+            data: encoded_data,
+            links: links.iter()
+                .map(|l| {
+                    JsonLink {
+                        name: &l.name,
+                        hash: l.object.hash(),
+                        size: l.object.size(),
+                    }
+                })
+                .collect(),
+        });
+
+        let args = put_args(self, "json", Some(datafieldenc))?;
+        let PutResult { hash } = api::post_data::<Json, PutResult>(self, "object/put", &args, &body)?;
+        // The node computes the object's encoding so ask it for the sizes.
+        let stat = self.stat(&hash)?;
+        let mut links = links;
+        bind_links(&mut links, self);
+        Ok(CommittedObject {
+            reference: Reference::new(stat.hash, stat.cumulative_size).with_client(self),
+            object: Object {
+                data: data,
+                links: links,
+            },
+            block_size: stat.block_size,
+        })
+    }
+
+    /// Get an object. See [object::get](object/fn.get.html).
+    pub fn get<P: IntoIpfsPath>(&self, path: P) -> io::Result<CommittedObject> {
+        self.get_with(path, &GetOptions::default())
+    }
+
+    /// Get an object with the specified options. See
+    /// [object::get_with](object/fn.get_with.html).
+    pub fn get_with<P: IntoIpfsPath>(&self, path: P, options: &GetOptions) -> io::Result<CommittedObject> {
+        let path = path.into_ipfs_path()?;
+        let result = self.lookup_with(&path, &StatOptions { offline: options.offline })
+            .and_then(|reference| self.fetch_with(&reference, options));
+        match result {
+            Err(ref e) if api::is_connection_error(e) && !options.offline &&
+                          path.namespace() == Namespace::Ipfs && path.remainder().is_none() &&
+                          self.gateway_fallback().is_some() => self.get_fallback(&path, options),
+            r => r,
+        }
+    }
+
+    // Gets an object's raw block from the fallback gateway.
+    fn get_fallback(&self, path: &IpfsPath, options: &GetOptions) -> io::Result<CommittedObject> {
+        let mut block = Vec::new();
+        match api::get_fallback(self, &format!("{}?format=raw", path)) {
+            Some(resp) => resp?.body.read_to_end(&mut block)?,
+            None => return Err(io::Error::new(io::ErrorKind::NotConnected, "no fallback gateway")),
+        };
+        // The gateway isn't trusted.
+        let mut object = Object::from_protobuf(&block)?;
+        bind_links(&mut object.links, self);
+        let size = object.links.iter().fold(block.len() as u64, |s, l| s + l.object.size());
+        let reference = Reference::new(path.root().to_owned(), size).with_client(self);
+        let committed = CommittedObject {
+            reference: reference,
+            object: object,
+            block_size: block.len() as u64,
+        };
+        verify_hash(&committed)?;
+        if let Some(max_size) = options.max_size {
+            if committed.size() > max_size {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          format!("object {} is too large ({} > {} bytes)",
+                                                  committed.hash(), committed.size(), max_size)));
+            }
+        }
+        Ok(committed)
+    }
+
+    /// Get the referenced object. See [Reference::get](object/struct.Reference.html#method.get).
+    pub fn fetch(&self, reference: &Reference) -> io::Result<CommittedObject> {
+        self.fetch_with(reference, &GetOptions::default())
+    }
+
+    /// Get the referenced object with the specified options. See
+    /// [Reference::get_with](object/struct.Reference.html#method.get_with).
+    pub fn fetch_with(&self, reference: &Reference, options: &GetOptions) -> io::Result<CommittedObject> {
+        if let Some(max_size) = options.max_size {
+            if reference.size > max_size {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          format!("object {} is too large ({} > {} bytes)",
+                                                  reference.hash, reference.size, max_size)));
+            }
+        }
+        let args = [("offline", api::bool_to_str(options.offline)), ("arg", &reference.hash)];
+        let node = match api::get::<Protobuf, merkledag::PBNode>(self, "object/get", &args) {
+            Ok(node) => node,
+            Err(ref e) if options.offline && e.is_api_error(api::ipfs_error::NOT_FOUND) => {
+                return Err(io::Error::new(io::ErrorKind::NotFound, "object not found locally"));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let encoded_size = node.compute_size() as u64;
+        let mut object = object_from_node(node)?;
+        bind_links(&mut object.links, self);
+
+        if options.verify_size {
+            let size = object.links.iter().fold(encoded_size, |s, l| s + l.object.size());
+            if size != reference.size {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                          format!("size mismatch for {}: expected {}, found {}",
+                                                  reference.hash, reference.size, size)));
+            }
+        }
+
+        Ok(CommittedObject {
+            reference: reference.clone().with_client(self),
+            object: object,
+            block_size: encoded_size,
+        })
+    }
+
+    /// Get many objects at once. See [object::get_many](object/fn.get_many.html).
+    pub fn get_many(&self, refs: &[Reference]) -> Vec<io::Result<CommittedObject>> {
+        self.get_many_with(refs, DEFAULT_GET_CONCURRENCY)
+    }
+
+    /// Get many objects at once, making at most `concurrency` requests at a
+    /// time. See [object::get_many_with](object/fn.get_many_with.html).
+    pub fn get_many_with(&self, refs: &[Reference], concurrency: usize) -> Vec<io::Result<CommittedObject>> {
+        let refs = refs.to_vec();
+        run_concurrently(self, refs.len(), concurrency, move |client, i| client.fetch(&refs[i]))
+    }
+
+    /// Stat many objects at once. See [object::stat_many](object/fn.stat_many.html).
+    pub fn stat_many(&self, paths: &[&str]) -> Vec<io::Result<Stat>> {
+        self.stat_many_with(paths, DEFAULT_GET_CONCURRENCY)
+    }
+
+    /// Stat many objects at once, making at most `concurrency` requests at a
+    /// time. See [object::stat_many_with](object/fn.stat_many_with.html).
+    pub fn stat_many_with(&self, paths: &[&str], concurrency: usize) -> Vec<io::Result<Stat>> {
+        let paths: Vec<String> = paths.iter().map(|&p| p.to_owned()).collect();
+        run_concurrently(self, paths.len(), concurrency, move |client, i| Ok(client.stat(&paths[i])?))
+    }
+
+    /// Get an object's links. See [object::links](object/fn.links.html).
+    pub fn links(&self, path: &str) -> io::Result<Vec<Link>> {
+        #[derive(Deserialize)]
+        struct LinksResult {
+            #[serde(rename="Links", default)]
+            links: Vec<LinkResult>,
+        }
+
+        #[derive(Deserialize)]
+        struct LinkResult {
+            #[serde(rename="Name")]
+            name: String,
+            #[serde(rename="Hash")]
+            hash: String,
+            #[serde(rename="Size")]
+            size: u64,
+        }
+
+        let resp = api::get::<Json, LinksResult>(self, "object/links", &[("arg", path)])?;
+        Ok(resp.links
+               .into_iter()
+               .map(|l| {
+                   Link {
+                       name: l.name,
+                       object: Reference::new(l.hash, l.size).with_client(self),
+                   }
+               })
+               .collect())
+    }
+
+    /// Stream an object's links. See
+    /// [object::get_links_streaming](object/fn.get_links_streaming.html).
+    pub fn get_links_streaming(&self, path: &str) -> io::Result<LinkStream> {
+        let resp = api::get_raw(self, "object/get", &[("encoding", "protobuf"), ("arg", path)])?;
+        Ok(LinkStream {
+            reader: BufReader::new(resp),
+            client: self.clone(),
+            done: false,
+        })
+    }
+
+    /// Lookup information about an object. See [object::stat](object/fn.stat.html).
+    pub fn stat<P: IntoIpfsPath>(&self, path: P) -> Result<Stat, StatError> {
+        self.stat_with(path, &StatOptions::default())
+    }
+
+    /// Lookup information about an object without touching the network. See
+    /// [object::stat_offline](object/fn.stat_offline.html).
+    pub fn stat_offline<P: IntoIpfsPath>(&self, path: P) -> Result<Stat, StatError> {
+        self.stat_with(path, &StatOptions { offline: true })
+    }
+
+    /// Lookup information about an object with the specified options. See
+    /// [object::stat_with](object/fn.stat_with.html).
+    pub fn stat_with<P: IntoIpfsPath>(&self, path: P, options: &StatOptions) -> Result<Stat, StatError> {
+        let path = path.into_ipfs_path().map_err(|e| StatError::InvalidPath(e.into()))?;
+        api::get::<Json, Stat>(self, "object/stat", &[("offline", api::bool_to_str(options.offline)), ("arg", path.as_str())])
+            .map_err(StatError::classify)
+    }
+
+    /// Get a reference to an object. See [object::lookup](object/fn.lookup.html).
+    pub fn lookup<P: IntoIpfsPath>(&self, path: P) -> io::Result<Reference> {
+        self.lookup_with(path, &StatOptions::default())
+    }
+
+    /// Get a reference to an object with the specified options. See
+    /// [object::lookup_with](object/fn.lookup_with.html).
+    pub fn lookup_with<P: IntoIpfsPath>(&self, path: P, options: &StatOptions) -> io::Result<Reference> {
+        let path = path.into_ipfs_path()?;
+        if let Some(reference) = api::cached_lookup(self, &path, options.offline) {
+            return Ok(reference);
+        }
+        let stats = self.stat_with(&path, options)?;
+        let reference = Reference::new(stats.hash, stats.cumulative_size).with_client(self);
+        api::cache_lookup(self, &path, options.offline, &reference);
+        Ok(reference)
+    }
+
+    /// Lookup the total size of an object. See
+    /// [object::stat_size_only](object/fn.stat_size_only.html).
+    pub fn stat_size_only<P: IntoIpfsPath>(&self, path: P) -> io::Result<u64> {
+        let path = path.into_ipfs_path()?;
+        if let Some(reference) = api::cached_lookup(self, &path, false) {
+            return Ok(reference.size());
+        }
+        let stat = api::get::<Json, CumulativeSize>(self, "object/stat", &[("arg", path.as_str())])
+            .map_err(StatError::classify)?;
+        Ok(stat.cumulative_size)
+    }
+
+    /// Unpin an object. See [Reference::unpin](object/struct.Reference.html#method.unpin).
+    pub fn unpin(&self, reference: &Reference, recursive: bool) -> io::Result<()> {
+        api::post::<Ignore, ()>(self, "pin/rm", &[("recursive", api::bool_to_str(recursive)), ("arg", reference.hash())])
+            .or_else(|e| {
+                if api::is_not_pinned(&e) {
+                    // We consider this to be a success. That is, the object is
+                    // no longer pinned.
+                    return Ok(());
+                }
+                debug_assert!(!e.is_api_error(api::ipfs_error::INVALID_REF), "sent an invalid ref to the server");
+                Err(e.into())
+            })
+    }
+
+    /// Pin an object. See [Reference::pin](object/struct.Reference.html#method.pin).
+    pub fn pin(&self, reference: &Reference, recursive: bool) -> io::Result<()> {
+        api::post::<Ignore, ()>(self, "pin/add", &[("recursive", api::bool_to_str(recursive)), ("arg", reference.hash())])?;
+        Ok(())
+    }
+
+    /// Pin an object with a name. See
+    /// [Reference::pin_named](object/struct.Reference.html#method.pin_named).
+    pub fn pin_named(&self, reference: &Reference, recursive: bool, name: &str) -> io::Result<()> {
+        api::post::<Ignore, ()>(self, "pin/add", &[
+            ("recursive", api::bool_to_str(recursive)),
+            ("name", name),
+            ("arg", reference.hash()),
+        ])?;
+        Ok(())
+    }
+
+    /// Pin an object, succeeding if it's already pinned. See
+    /// [Reference::ensure_pinned](object/struct.Reference.html#method.ensure_pinned).
+    pub fn ensure_pinned(&self, reference: &Reference, recursive: bool) -> io::Result<()> {
+        match api::post::<Ignore, ()>(self, "pin/add", &[("recursive", api::bool_to_str(recursive)), ("arg", reference.hash())]) {
+            Err(ref e) if api::is_already_pinned(e) => Ok(()),
+            Err(e) => Err(e.into()),
+            Ok(()) => Ok(()),
+        }
+    }
+
+    /// Unpin an object, succeeding if it isn't pinned. See
+    /// [Reference::ensure_unpinned](object/struct.Reference.html#method.ensure_unpinned).
+    pub fn ensure_unpinned(&self, reference: &Reference, recursive: bool) -> io::Result<()> {
+        // Unpinning already succeeds when the object isn't pinned.
+        self.unpin(reference, recursive)
+    }
+
+    /// List the changes between two DAGs. See [object::diff](object/fn.diff.html).
+    pub fn diff(&self, a: &str, b: &str) -> io::Result<Vec<ObjectChange>> {
+        #[derive(Deserialize)]
+        struct DiffResult {
+            #[serde(rename="Changes", default)]
+            changes: Option<Vec<ChangeResult>>,
+        }
+
+        #[derive(Deserialize)]
+        struct ChangeResult {
+            #[serde(rename="Type")]
+            change_type: u8,
+            #[serde(rename="Path")]
+            path: String,
+            #[serde(rename="Before", default)]
+            before: Option<CidLink>,
+            #[serde(rename="After", default)]
+            after: Option<CidLink>,
+        }
+
+        #[derive(Deserialize)]
+        struct CidLink {
+            #[serde(rename="/")]
+            cid: String,
+        }
+
+        let resp = api::get::<Json, DiffResult>(self, "object/diff", &[("arg", a), ("arg", b)])?;
+        resp.changes
+            .unwrap_or_else(Vec::new)
+            .into_iter()
+            .map(|c| {
+                let change_type = match c.change_type {
+                    0 => ChangeType::Add,
+                    1 => ChangeType::Remove,
+                    2 => ChangeType::Modify,
+                    _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "unknown change type")),
+                };
+                Ok(ObjectChange {
+                    change_type: change_type,
+                    path: c.path,
+                    before: c.before.map(|l| l.cid),
+                    after: c.after.map(|l| l.cid),
+                })
+            })
+            .collect()
+    }
+}
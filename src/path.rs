@@ -0,0 +1,198 @@
+//! Typed IPFS paths.
+//!
+//! Functions that look up objects (e.g. [object::get](../object/fn.get.html))
+//! accept bare hashes (`Qm...`), `/ipfs/` paths, and `/ipns/` paths. They
+//! parse and validate their argument as an [IpfsPath](struct.IpfsPath.html)
+//! before talking to the IPFS node.
+use std::io;
+use std::fmt;
+use std::str::FromStr;
+
+use cid::Cid;
+use multihash::CidError;
+use object::Reference;
+
+/// The namespace of an [IpfsPath](struct.IpfsPath.html).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Namespace {
+    /// An immutable path rooted at a CID (`/ipfs/...`).
+    Ipfs,
+    /// A mutable path rooted at an IPNS name or DNSLink domain (`/ipns/...`).
+    Ipns,
+}
+
+impl Namespace {
+    /// The namespace's path prefix (without slashes).
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Namespace::Ipfs => "ipfs",
+            Namespace::Ipns => "ipns",
+        }
+    }
+}
+
+/// A validated IPFS path.
+///
+/// Bare hashes are normalized to `/ipfs/` paths.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct IpfsPath {
+    namespace: Namespace,
+    // The full, normalized path.
+    path: String,
+    // The length of `/<namespace>/<root>`.
+    root_end: usize,
+}
+
+// Multibase prefixes of CID encodings that the IPFS node may understand but
+// that we can't decode (e.g. base36, `k...`).
+const OTHER_MULTIBASES: &'static str = "079fFvVtTcCBhkKZmMuU";
+
+fn is_valid_root(root: &str) -> bool {
+    match Cid::parse(root) {
+        Ok(_) => true,
+        // Leave validating these to the IPFS node.
+        Err(CidError::UnsupportedMultibase) => {
+            let mut chars = root.chars();
+            chars.next().map_or(false, |c| OTHER_MULTIBASES.contains(c)) &&
+            chars.all(|c| c.is_digit(36) || c == '-' || c == '_' || c == '+' || c == '=')
+        }
+        Err(_) => false,
+    }
+}
+
+fn invalid(path: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("invalid ipfs path: {:?}", path))
+}
+
+impl IpfsPath {
+    /// Parse a bare hash (e.g. `Qm...`), an `/ipfs/` path, or an `/ipns/`
+    /// path.
+    ///
+    /// The roots of `/ipfs/` paths (and bare hashes) must be valid CIDs. CIDs in
+    /// multibase encodings this crate can't decode (e.g. base36) are passed on
+    /// to the IPFS node as-is.
+    pub fn parse(s: &str) -> io::Result<IpfsPath> {
+        let (namespace, rest) = if s.starts_with("/ipfs/") {
+            (Namespace::Ipfs, &s[6..])
+        } else if s.starts_with("/ipns/") {
+            (Namespace::Ipns, &s[6..])
+        } else if s.starts_with('/') {
+            return Err(invalid(s));
+        } else {
+            (Namespace::Ipfs, s)
+        };
+
+        let root = rest.split('/').next().unwrap();
+        if root.is_empty() {
+            return Err(invalid(s));
+        }
+        if namespace == Namespace::Ipfs && !is_valid_root(root) {
+            return Err(invalid(s));
+        }
+
+        let path = format!("/{}/{}", namespace.as_str(), rest.trim_right_matches('/'));
+        Ok(IpfsPath {
+            namespace: namespace,
+            root_end: namespace.as_str().len() + root.len() + 2,
+            path: path,
+        })
+    }
+
+    /// The path's namespace.
+    pub fn namespace(&self) -> Namespace {
+        self.namespace
+    }
+
+    /// The path's root: a CID for `/ipfs/` paths, an IPNS name or domain for
+    /// `/ipns/` paths.
+    pub fn root(&self) -> &str {
+        &self.path[self.namespace.as_str().len() + 2..self.root_end]
+    }
+
+    /// The rest of the path after the root (without a leading slash), if any.
+    pub fn remainder(&self) -> Option<&str> {
+        if self.path.len() > self.root_end {
+            Some(&self.path[self.root_end + 1..])
+        } else {
+            None
+        }
+    }
+
+    /// The normalized path as a string.
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for IpfsPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl FromStr for IpfsPath {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<IpfsPath> {
+        IpfsPath::parse(s)
+    }
+}
+
+impl AsRef<str> for IpfsPath {
+    fn as_ref(&self) -> &str {
+        &self.path
+    }
+}
+
+impl<'a> From<&'a Reference> for IpfsPath {
+    fn from(r: &'a Reference) -> IpfsPath {
+        IpfsPath {
+            namespace: Namespace::Ipfs,
+            path: format!("/ipfs/{}", r.hash()),
+            root_end: r.hash().len() + 6,
+        }
+    }
+}
+
+/// Something that can be converted into an [IpfsPath](struct.IpfsPath.html),
+/// possibly failing (e.g. strings).
+pub trait IntoIpfsPath {
+    /// Parse this into an `IpfsPath`.
+    fn into_ipfs_path(self) -> io::Result<IpfsPath>;
+}
+
+impl IntoIpfsPath for IpfsPath {
+    fn into_ipfs_path(self) -> io::Result<IpfsPath> {
+        Ok(self)
+    }
+}
+
+impl<'a> IntoIpfsPath for &'a IpfsPath {
+    fn into_ipfs_path(self) -> io::Result<IpfsPath> {
+        Ok(self.clone())
+    }
+}
+
+impl<'a> IntoIpfsPath for &'a Reference {
+    fn into_ipfs_path(self) -> io::Result<IpfsPath> {
+        Ok(self.into())
+    }
+}
+
+impl<'a> IntoIpfsPath for &'a str {
+    fn into_ipfs_path(self) -> io::Result<IpfsPath> {
+        IpfsPath::parse(self)
+    }
+}
+
+impl<'a> IntoIpfsPath for &'a String {
+    fn into_ipfs_path(self) -> io::Result<IpfsPath> {
+        IpfsPath::parse(self)
+    }
+}
+
+impl IntoIpfsPath for String {
+    fn into_ipfs_path(self) -> io::Result<IpfsPath> {
+        IpfsPath::parse(&self)
+    }
+}
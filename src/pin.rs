@@ -0,0 +1,126 @@
+//! API for inspecting the pinset.
+use std::io;
+use std::collections::BTreeMap;
+
+use serde;
+
+use api::{self, Client};
+use object::Reference;
+use encoding::Json;
+
+/// The type of a pin.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PinType {
+    /// The object itself is pinned (but not its children).
+    Direct,
+    /// The object and all of its descendants are pinned.
+    Recursive,
+    /// The object is pinned because an ancestor is recursively pinned.
+    Indirect,
+    /// Any of the above (only valid as a filter).
+    All,
+}
+
+impl PinType {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            PinType::Direct => "direct",
+            PinType::Recursive => "recursive",
+            PinType::Indirect => "indirect",
+            PinType::All => "all",
+        }
+    }
+}
+
+impl serde::Deserialize for PinType {
+    fn deserialize<D: serde::Deserializer>(d: &mut D) -> Result<PinType, D::Error> {
+        struct Visitor;
+        impl serde::de::Visitor for Visitor {
+            type Value = PinType;
+            fn visit_str<E: serde::de::Error>(&mut self, v: &str) -> Result<PinType, E> {
+                Ok(match v {
+                    "direct" => PinType::Direct,
+                    "recursive" => PinType::Recursive,
+                    // Listing a specific object reports "indirect through <hash>".
+                    _ if v.starts_with("indirect") => PinType::Indirect,
+                    _ => return Err(E::invalid_value(v)),
+                })
+            }
+        }
+        d.deserialize_str(Visitor)
+    }
+}
+
+#[derive(Deserialize)]
+struct PinLsResult {
+    #[serde(rename="Keys")]
+    keys: BTreeMap<String, PinInfo>,
+}
+
+#[derive(Deserialize)]
+struct PinInfo {
+    #[serde(rename="Type")]
+    pin_type: PinType,
+    #[serde(rename="Name", default)]
+    name: Option<String>,
+}
+
+/// List the hashes of the pinned objects of the given type.
+///
+/// Use [object::lookup](../object/fn.lookup.html) to get a reference to a
+/// pinned object.
+pub fn pinned(pin_type: PinType) -> io::Result<Vec<(String, PinType)>> {
+    api::default_client().pinned(pin_type)
+}
+
+/// List the pinned objects of the given type along with their names (see
+/// [Reference::pin_named](../object/struct.Reference.html#method.pin_named)).
+///
+/// Pins without a name (and all pins on IPFS nodes that don't support pin
+/// names) have a name of `None`. See [pinned](fn.pinned.html).
+pub fn pinned_with_names(pin_type: PinType) -> io::Result<Vec<(String, PinType, Option<String>)>> {
+    api::default_client().pinned_with_names(pin_type)
+}
+
+impl Client {
+    /// List the pinned objects of the given type. See [pin::pinned](pin/fn.pinned.html).
+    pub fn pinned(&self, pin_type: PinType) -> io::Result<Vec<(String, PinType)>> {
+        Ok(self.pin_ls(pin_type, false)?
+            .into_iter()
+            .map(|(hash, pin_type, _)| (hash, pin_type))
+            .collect())
+    }
+
+    /// List the pinned objects of the given type along with their names. See
+    /// [pin::pinned_with_names](pin/fn.pinned_with_names.html).
+    pub fn pinned_with_names(&self, pin_type: PinType) -> io::Result<Vec<(String, PinType, Option<String>)>> {
+        self.pin_ls(pin_type, true)
+    }
+
+    // Only asks for names when needed as older IPFS nodes reject the option.
+    fn pin_ls(&self, pin_type: PinType, names: bool) -> io::Result<Vec<(String, PinType, Option<String>)>> {
+        let mut args = vec![("type", pin_type.as_str())];
+        if names {
+            args.push(("names", "true"));
+        }
+        let resp = api::get::<Json, PinLsResult>(self, "pin/ls", &args)?;
+        Ok(resp.keys
+            .into_iter()
+            .map(|(hash, info)| {
+                let name = info.name.and_then(|n| if n.is_empty() { None } else { Some(n) });
+                (hash, info.pin_type, name)
+            })
+            .collect())
+    }
+
+    /// Check how (if at all) an object is pinned. See
+    /// [Reference::is_pinned](object/struct.Reference.html#method.is_pinned).
+    pub fn is_pinned(&self, reference: &Reference) -> io::Result<Option<PinType>> {
+        let resp = match api::get::<Json, PinLsResult>(self, "pin/ls", &[("type", "all"), ("arg", reference.hash())]) {
+            Ok(resp) => resp,
+            Err(ref e) if api::is_not_pinned(e) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(resp.keys.into_iter().next().map(|(_, info)| info.pin_type))
+    }
+}
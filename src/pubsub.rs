@@ -0,0 +1,147 @@
+//! API for publishing and subscribing to pubsub topics.
+use std::io;
+use std::time::Duration;
+
+use base58::ToBase58;
+use rustc_serialize::base64::FromBase64;
+
+use api::{self, Client};
+use encoding::{Ignore, JsonStream};
+
+/// A pubsub message.
+///
+/// Yielded by [Subscription](struct.Subscription.html).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Message {
+    /// The ID of the peer that published the message.
+    pub from: String,
+    /// The message's data.
+    pub data: Vec<u8>,
+    /// The message's sequence number.
+    pub seqno: Vec<u8>,
+    /// The topics to which the message was published.
+    pub topics: Vec<String>,
+}
+
+/// Publish data to a topic.
+pub fn publish(topic: &str, data: &[u8]) -> io::Result<()> {
+    api::default_client().pubsub_publish(topic, data)
+}
+
+/// Subscribe to a topic.
+///
+/// The subscription yields messages as they arrive and only ends when the
+/// connection is closed. Don't set a timeout (see
+/// [set_timeout](../fn.set_timeout.html)) on the client if you expect the topic
+/// to be quiet for long periods of time; use
+/// [Subscription::set_read_timeout](struct.Subscription.html#method.set_read_timeout)
+/// to stop waiting for messages instead.
+pub fn subscribe(topic: &str) -> io::Result<Subscription> {
+    api::default_client().pubsub_subscribe(topic)
+}
+
+/// A subscription to a pubsub topic.
+///
+/// Returned from [subscribe](fn.subscribe.html). If the stream is malformed,
+/// the iterator yields an error and then stops.
+///
+/// Dropping the subscription closes the connection, unsubscribing from the
+/// topic, unless a read timeout has been set: the connection is then only
+/// closed when the next message arrives (see
+/// [set_read_timeout](#method.set_read_timeout)).
+pub struct Subscription {
+    messages: JsonStream<MessageResult>,
+    done: bool,
+}
+
+#[derive(Deserialize)]
+struct MessageResult {
+    #[serde(default)]
+    from: String,
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    seqno: String,
+    #[serde(rename="topicIDs", default)]
+    topics: Option<Vec<String>>,
+}
+
+impl Subscription {
+    /// Give up waiting for the next message after `timeout` (default: wait
+    /// forever), yielding an `io::ErrorKind::TimedOut` error instead.
+    ///
+    /// The iterator can still be used after timing out, so this can be used to
+    /// periodically check whether to stop listening. Unlike the client's
+    /// timeout, this only applies to waiting for messages, not to the initial
+    /// request.
+    ///
+    /// Setting a timeout hands the connection to a background thread that
+    /// waits for messages. Once this is done, dropping the subscription no
+    /// longer closes the connection right away: the node keeps the
+    /// subscription open until the next message on the topic arrives and the
+    /// thread notices that nobody is listening.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.messages.set_read_timeout(timeout);
+    }
+
+    /// Get the read timeout.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.messages.read_timeout()
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = io::Result<Message>;
+
+    fn next(&mut self) -> Option<io::Result<Message>> {
+        if self.done {
+            return None;
+        }
+        while let Some(msg) = self.messages.next() {
+            let msg = match msg {
+                Ok(msg) => msg,
+                Err(e) => return Some(Err(e)),
+            };
+            // Some versions of go-ipfs send an empty message when the
+            // subscription starts.
+            if msg.from.is_empty() && msg.seqno.is_empty() {
+                continue;
+            }
+            let msg = decode_message(msg);
+            if msg.is_err() {
+                self.done = true;
+            }
+            return Some(msg);
+        }
+        None
+    }
+}
+
+fn decode_message(msg: MessageResult) -> io::Result<Message> {
+    Ok(Message {
+        from: base64_decode(&msg.from)?.to_base58(),
+        data: base64_decode(&msg.data)?,
+        seqno: base64_decode(&msg.seqno)?,
+        topics: msg.topics.unwrap_or_else(Vec::new),
+    })
+}
+
+fn base64_decode(s: &str) -> io::Result<Vec<u8>> {
+    s.from_base64().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+impl Client {
+    /// Publish data to a topic. See [pubsub::publish](pubsub/fn.publish.html).
+    pub fn pubsub_publish(&self, topic: &str, data: &[u8]) -> io::Result<()> {
+        Ok(api::post_data::<Ignore, ()>(self, "pubsub/pub", &[("arg", topic)], data)?)
+    }
+
+    /// Subscribe to a topic. See [pubsub::subscribe](pubsub/fn.subscribe.html).
+    pub fn pubsub_subscribe(&self, topic: &str) -> io::Result<Subscription> {
+        let resp = api::get_raw(self, "pubsub/sub", &[("arg", topic), ("discover", "true")])?;
+        Ok(Subscription {
+            messages: JsonStream::new(resp),
+            done: false,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+//! API for walking the DAG.
+use std::io;
+
+use api::{self, Client};
+use encoding::JsonStream;
+
+/// Options for [refs](fn.refs.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct RefsOptions {
+    /// Recursively list the references of children.
+    pub recursive: bool,
+    /// Omit duplicate references.
+    pub unique: bool,
+    /// Only descend this many levels (implies `recursive`).
+    pub max_depth: Option<u32>,
+}
+
+/// List the hashes of the objects linked to by the object at the given path.
+///
+/// This doesn't materialize the objects so it's much cheaper than walking the
+/// DAG with [get](../object/fn.get.html).
+pub fn refs(path: &str, opts: RefsOptions) -> io::Result<Vec<String>> {
+    api::default_client().refs(path, opts)
+}
+
+impl Client {
+    /// List the hashes of the objects linked to by the object at the given path. See
+    /// [refs::refs](refs/fn.refs.html).
+    pub fn refs(&self, path: &str, opts: RefsOptions) -> io::Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct RefResult {
+            #[serde(rename="Ref")]
+            reference: String,
+            #[serde(rename="Err", default)]
+            err: String,
+        }
+
+        let max_depth = opts.max_depth.map(|d| d.to_string());
+        let mut args = vec![
+            ("encoding", "json"),
+            ("recursive", api::bool_to_str(opts.recursive || opts.max_depth.is_some())),
+            ("unique", api::bool_to_str(opts.unique)),
+        ];
+        if let Some(ref max_depth) = max_depth {
+            args.push(("max-depth", &max_depth[..]));
+        }
+        args.push(("arg", path));
+
+        let mut refs = Vec::new();
+        for r in JsonStream::<RefResult>::new(api::get_raw(self, "refs", &args)?) {
+            let r = r?;
+            if !r.err.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::Other, r.err));
+            }
+            refs.push(r.reference);
+        }
+        Ok(refs)
+    }
+}
@@ -0,0 +1,160 @@
+//! API for managing the IPFS node's repository.
+use std::io;
+use std::cmp;
+use std::mem;
+
+use api::{self, Client};
+use encoding::{Json, JsonStream};
+
+/// Run the garbage collector, returning the hashes of the removed objects.
+pub fn gc() -> io::Result<Vec<String>> {
+    api::default_client().gc()
+}
+
+/// Statistics about the IPFS node's repository.
+///
+/// Returned from [repo_stat](fn.repo_stat.html).
+#[derive(Deserialize, Debug)]
+pub struct RepoStat {
+    /// The size of the repository in bytes.
+    #[serde(rename="RepoSize")]
+    pub repo_size: u64,
+
+    /// The number of objects in the repository.
+    #[serde(rename="NumObjects")]
+    pub num_objects: u64,
+
+    /// The maximum size of the repository in bytes.
+    #[serde(rename="StorageMax")]
+    pub storage_max: u64,
+
+    #[doc(hidden)]
+    #[serde(default)]
+    _non_exhaustive: (),
+}
+
+/// Get statistics about the IPFS node's repository.
+pub fn repo_stat() -> io::Result<RepoStat> {
+    api::default_client().repo_stat()
+}
+
+/// The result of verifying the IPFS node's repository.
+///
+/// Returned from [repo_verify](fn.repo_verify.html).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RepoVerify {
+    /// The number of blocks checked.
+    pub blocks_checked: u64,
+    /// The problems found (e.g. corrupt blocks), one message per problem.
+    pub problems: Vec<String>,
+    /// The IPFS node's summary.
+    pub message: String,
+}
+
+impl RepoVerify {
+    /// Check whether every block was valid.
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Verify that every block in the IPFS node's repository is intact.
+///
+/// This reads the entire repository so it can take a long time.
+pub fn repo_verify() -> io::Result<RepoVerify> {
+    api::default_client().repo_verify()
+}
+
+/// Get the version of the IPFS node's repository format.
+///
+/// IPFS nodes refuse to start with repositories in older formats until they've
+/// been migrated (with `fs-repo-migrations`).
+pub fn repo_version() -> io::Result<u32> {
+    api::default_client().repo_version()
+}
+
+impl Client {
+    /// Run the garbage collector, returning the hashes of the removed objects. See
+    /// [repo::gc](repo/fn.gc.html).
+    pub fn gc(&self) -> io::Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct GcResult {
+            #[serde(rename="Key", default)]
+            key: Option<CidLink>,
+            #[serde(rename="Error", default)]
+            error: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct CidLink {
+            #[serde(rename="/")]
+            cid: String,
+        }
+
+        let resp = api::post_raw(self, "repo/gc", &[("encoding", "json")])?;
+        let mut removed = Vec::new();
+        for r in JsonStream::<GcResult>::new(resp) {
+            let r = r?;
+            if let Some(e) = r.error {
+                return Err(io::Error::new(io::ErrorKind::Other, e));
+            }
+            if let Some(key) = r.key {
+                removed.push(key.cid);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Get statistics about the IPFS node's repository. See
+    /// [repo::repo_stat](repo/fn.repo_stat.html).
+    pub fn repo_stat(&self) -> io::Result<RepoStat> {
+        Ok(api::get::<Json, RepoStat>(self, "repo/stat", &[])?)
+    }
+
+    /// Verify the IPFS node's repository. See [repo::repo_verify](repo/fn.repo_verify.html).
+    pub fn repo_verify(&self) -> io::Result<RepoVerify> {
+        #[derive(Deserialize)]
+        struct VerifyProgress {
+            #[serde(rename="Msg", default)]
+            msg: String,
+            #[serde(rename="Progress", default)]
+            progress: u64,
+        }
+
+        let resp = api::post_raw(self, "repo/verify", &[])?;
+        let mut verify = RepoVerify {
+            blocks_checked: 0,
+            problems: Vec::new(),
+            message: String::new(),
+        };
+        for progress in JsonStream::<VerifyProgress>::new(resp) {
+            let progress = progress?;
+            verify.blocks_checked = cmp::max(verify.blocks_checked, progress.progress);
+            if !progress.msg.is_empty() {
+                // Only the last message is the summary.
+                let msg = mem::replace(&mut verify.message, progress.msg);
+                if !msg.is_empty() {
+                    verify.problems.push(msg);
+                }
+            }
+        }
+        Ok(verify)
+    }
+
+    /// Get the repository format version. See
+    /// [repo::repo_version](repo/fn.repo_version.html).
+    pub fn repo_version(&self) -> io::Result<u32> {
+        #[derive(Deserialize)]
+        struct VersionResult {
+            #[serde(rename="Version")]
+            version: String,
+        }
+
+        let resp = api::get::<Json, VersionResult>(self, "repo/version", &[])?;
+        resp.version
+            .trim()
+            .trim_left_matches("fs-repo@")
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
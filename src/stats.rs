@@ -0,0 +1,55 @@
+//! API for monitoring the node.
+use std::io;
+
+use api::{self, Client};
+use encoding::Json;
+
+/// Options for [bandwidth](fn.bandwidth.html).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct BandwidthOptions<'a> {
+    /// Only count traffic to/from this peer.
+    pub peer: Option<&'a str>,
+    /// Only count traffic for this protocol (e.g. `/ipfs/bitswap/1.1.0`).
+    pub proto: Option<&'a str>,
+}
+
+/// Bandwidth usage, returned from [bandwidth](fn.bandwidth.html).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Bandwidth {
+    /// The total number of bytes received.
+    #[serde(rename="TotalIn")]
+    pub total_in: u64,
+    /// The total number of bytes sent.
+    #[serde(rename="TotalOut")]
+    pub total_out: u64,
+    /// The current receive rate in bytes per second.
+    #[serde(rename="RateIn")]
+    pub rate_in: f64,
+    /// The current send rate in bytes per second.
+    #[serde(rename="RateOut")]
+    pub rate_out: f64,
+}
+
+/// Get a snapshot of the node's bandwidth usage.
+///
+/// At most one of `peer` and `proto` may be specified. Polling (streaming
+/// periodic snapshots) isn't supported; call this function periodically
+/// instead.
+pub fn bandwidth(opts: BandwidthOptions) -> io::Result<Bandwidth> {
+    api::default_client().bandwidth(opts)
+}
+
+impl Client {
+    /// Get a snapshot of the node's bandwidth usage. See
+    /// [stats::bandwidth](stats/fn.bandwidth.html).
+    pub fn bandwidth(&self, opts: BandwidthOptions) -> io::Result<Bandwidth> {
+        let mut args = vec![];
+        if let Some(peer) = opts.peer {
+            args.push(("peer", peer));
+        }
+        if let Some(proto) = opts.proto {
+            args.push(("proto", proto));
+        }
+        Ok(api::get::<Json, Bandwidth>(self, "stats/bw", &args)?)
+    }
+}
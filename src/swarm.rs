@@ -0,0 +1,175 @@
+//! API for managing the node's peer connections.
+use std::io;
+use std::collections::HashMap;
+
+use serde;
+
+use api::{self, Client};
+use encoding::Json;
+
+/// The direction of a connection.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    /// The direction is unknown.
+    Unknown,
+    /// The peer connected to us.
+    Inbound,
+    /// We connected to the peer.
+    Outbound,
+}
+
+impl serde::Deserialize for Direction {
+    fn deserialize<D: serde::Deserializer>(d: &mut D) -> Result<Direction, D::Error> {
+        struct Visitor;
+        impl serde::de::Visitor for Visitor {
+            type Value = Direction;
+            fn visit_u64<E: serde::de::Error>(&mut self, v: u64) -> Result<Direction, E> {
+                Ok(match v {
+                    1 => Direction::Inbound,
+                    2 => Direction::Outbound,
+                    _ => Direction::Unknown,
+                })
+            }
+        }
+        d.deserialize_u64(Visitor)
+    }
+}
+
+/// A connected peer.
+///
+/// Returned from [peers](fn.peers.html).
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct PeerInfo {
+    /// The multiaddr of the connection.
+    #[serde(rename="Addr")]
+    pub addr: String,
+
+    /// The peer's ID.
+    #[serde(rename="Peer")]
+    pub peer: String,
+
+    /// The connection's latency (e.g. "12ms"), if known.
+    #[serde(rename="Latency", default)]
+    pub latency: Option<String>,
+
+    /// The direction of the connection, if known.
+    #[serde(rename="Direction", default)]
+    pub direction: Option<Direction>,
+}
+
+/// List the peers this node is connected to.
+pub fn peers() -> io::Result<Vec<PeerInfo>> {
+    api::default_client().swarm_peers()
+}
+
+/// Connect to the peer at the given multiaddr.
+pub fn connect(multiaddr: &str) -> io::Result<()> {
+    api::default_client().swarm_connect(multiaddr)
+}
+
+/// Disconnect from the peer at the given multiaddr.
+pub fn disconnect(multiaddr: &str) -> io::Result<()> {
+    api::default_client().swarm_disconnect(multiaddr)
+}
+
+/// List the known addresses of every peer in the node's address book, keyed by
+/// peer ID.
+pub fn addrs() -> io::Result<HashMap<String, Vec<String>>> {
+    api::default_client().swarm_addrs()
+}
+
+/// List the address filters (multiaddrs with a netmask, e.g.
+/// `/ip4/10.0.0.0/ipcidr/8`) the node refuses to dial or accept connections
+/// from.
+pub fn filters() -> io::Result<Vec<String>> {
+    api::default_client().swarm_filters()
+}
+
+/// Add an address filter. See [filters](fn.filters.html).
+///
+/// Filters added this way aren't persisted in the node's config.
+pub fn add_filter(filter: &str) -> io::Result<()> {
+    api::default_client().swarm_add_filter(filter)
+}
+
+/// Remove an address filter. See [filters](fn.filters.html).
+pub fn rm_filter(filter: &str) -> io::Result<()> {
+    api::default_client().swarm_rm_filter(filter)
+}
+
+#[derive(Deserialize)]
+struct StringsResult {
+    #[serde(rename="Strings", default)]
+    strings: Vec<String>,
+}
+
+// Nodes report each address as "<command> <peer> success" or
+// "<command> <peer> failure: <error>".
+fn check_strings(resp: StringsResult) -> io::Result<()> {
+    match resp.strings.into_iter().find(|s| s.splitn(3, ' ').nth(2).map_or(false, |r| r.starts_with("failure: "))) {
+        Some(failure) => Err(io::Error::new(io::ErrorKind::Other, failure)),
+        None => Ok(()),
+    }
+}
+
+// Unlike connect/disconnect, the filter commands return `null` when there's
+// nothing to list.
+#[derive(Deserialize)]
+struct FiltersResult {
+    #[serde(rename="Strings", default)]
+    strings: Option<Vec<String>>,
+}
+
+impl Client {
+    /// List the peers this node is connected to. See [swarm::peers](swarm/fn.peers.html).
+    pub fn swarm_peers(&self) -> io::Result<Vec<PeerInfo>> {
+        #[derive(Deserialize)]
+        struct PeersResult {
+            #[serde(rename="Peers", default)]
+            peers: Option<Vec<PeerInfo>>,
+        }
+
+        let resp = api::get::<Json, PeersResult>(self, "swarm/peers", &[("verbose", "true"), ("direction", "true")])?;
+        Ok(resp.peers.unwrap_or_else(Vec::new))
+    }
+
+    /// Connect to the peer at the given multiaddr. See [swarm::connect](swarm/fn.connect.html).
+    pub fn swarm_connect(&self, multiaddr: &str) -> io::Result<()> {
+        check_strings(api::post::<Json, StringsResult>(self, "swarm/connect", &[("arg", multiaddr)])?)
+    }
+
+    /// Disconnect from the peer at the given multiaddr. See
+    /// [swarm::disconnect](swarm/fn.disconnect.html).
+    pub fn swarm_disconnect(&self, multiaddr: &str) -> io::Result<()> {
+        check_strings(api::post::<Json, StringsResult>(self, "swarm/disconnect", &[("arg", multiaddr)])?)
+    }
+
+    /// List the known addresses of every peer. See [swarm::addrs](swarm/fn.addrs.html).
+    pub fn swarm_addrs(&self) -> io::Result<HashMap<String, Vec<String>>> {
+        #[derive(Deserialize)]
+        struct AddrsResult {
+            #[serde(rename="Addrs", default)]
+            addrs: Option<HashMap<String, Vec<String>>>,
+        }
+
+        let resp = api::get::<Json, AddrsResult>(self, "swarm/addrs", &[])?;
+        Ok(resp.addrs.unwrap_or_else(HashMap::new))
+    }
+
+    /// List the address filters. See [swarm::filters](swarm/fn.filters.html).
+    pub fn swarm_filters(&self) -> io::Result<Vec<String>> {
+        Ok(api::get::<Json, FiltersResult>(self, "swarm/filters", &[])?.strings.unwrap_or_else(Vec::new))
+    }
+
+    /// Add an address filter. See [swarm::add_filter](swarm/fn.add_filter.html).
+    pub fn swarm_add_filter(&self, filter: &str) -> io::Result<()> {
+        api::post::<Json, FiltersResult>(self, "swarm/filters/add", &[("arg", filter)])?;
+        Ok(())
+    }
+
+    /// Remove an address filter. See [swarm::rm_filter](swarm/fn.rm_filter.html).
+    pub fn swarm_rm_filter(&self, filter: &str) -> io::Result<()> {
+        api::post::<Json, FiltersResult>(self, "swarm/filters/rm", &[("arg", filter)])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! API for exporting and importing DAGs as TAR archives.
+use std::io::{self, Read};
+
+use api::{self, Client};
+use object::Reference;
+use encoding::Json;
+use error::Error;
+
+/// A streaming reader over a TAR archive.
+///
+/// Returned from [export_tar](fn.export_tar.html) and
+/// [cat_tar](fn.cat_tar.html).
+pub struct TarReader {
+    response: Box<Read + Send>,
+}
+
+impl Read for TarReader {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.response.read(buf).map_err(|e| io::Error::from(Error::from(e)))
+    }
+}
+
+/// Export the file or directory at the given path as a TAR archive.
+///
+/// The archive is streamed from the IPFS node rather than being buffered into
+/// memory.
+pub fn export_tar(path: &str) -> io::Result<TarReader> {
+    api::default_client().export_tar(path)
+}
+
+/// Import a TAR archive, returning a reference to the root of the imported
+/// DAG.
+///
+/// The archive is stored as-is (headers included) so it can be read back with
+/// [cat_tar](fn.cat_tar.html); this is *not* the same as adding the extracted
+/// files.
+pub fn import_tar<R: Read>(reader: R) -> io::Result<Reference> {
+    api::default_client().import_tar(reader)
+}
+
+/// Read back an archive imported with [import_tar](fn.import_tar.html).
+pub fn cat_tar(path: &str) -> io::Result<TarReader> {
+    api::default_client().cat_tar(path)
+}
+
+impl Client {
+    /// Export a path as a TAR archive. See [tar::export_tar](tar/fn.export_tar.html).
+    pub fn export_tar(&self, path: &str) -> io::Result<TarReader> {
+        Ok(TarReader {
+            response: api::get_raw(self, "get", &[("archive", "true"), ("arg", path)])?,
+        })
+    }
+
+    /// Import a TAR archive. See [tar::import_tar](tar/fn.import_tar.html).
+    pub fn import_tar<R: Read>(&self, mut reader: R) -> io::Result<Reference> {
+        #[derive(Deserialize)]
+        struct TarAddResult {
+            #[serde(rename="Hash")]
+            hash: String,
+        }
+
+        let TarAddResult { hash } = api::post_reader::<Json, TarAddResult, _>(self, "tar/add", &[], &mut reader, None, None)?;
+        self.lookup(&hash)
+    }
+
+    /// Read back an imported TAR archive. See [tar::cat_tar](tar/fn.cat_tar.html).
+    pub fn cat_tar(&self, path: &str) -> io::Result<TarReader> {
+        Ok(TarReader {
+            response: api::get_raw(self, "tar/cat", &[("arg", path)])?,
+        })
+    }
+}
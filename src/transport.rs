@@ -0,0 +1,93 @@
+//! Pluggable transports for talking to the IPFS API.
+//!
+//! By default, clients talk to the IPFS API over HTTP. Implement
+//! [Transport](trait.Transport.html) and create a client with
+//! [Client::with_transport](../struct.Client.html#method.with_transport) to
+//! send requests somewhere else (e.g. to canned responses in tests).
+use std::io::Read;
+use std::time::Duration;
+
+use hyper::net::{NetworkConnector, NetworkStream};
+use hyper::client::pool::Pool;
+use hyper::client::request::Request as HttpRequest;
+use multipart::client::Multipart;
+
+pub use hyper::client::pool::Config as PoolConfig;
+pub use hyper::header::Headers;
+pub use hyper::method::Method;
+pub use hyper::mime::Mime;
+pub use url::Url;
+
+use error::Result;
+
+/// A request to the IPFS API.
+pub struct Request<'a> {
+    /// The HTTP method.
+    pub method: Method,
+    /// The full request URL including the API method (e.g. `object/stat`) and
+    /// its arguments.
+    pub url: Url,
+    /// Extra request headers (e.g. `Authorization`).
+    pub headers: Headers,
+    /// The read/write timeout.
+    pub timeout: Option<Duration>,
+    /// The files to upload, if any.
+    pub uploads: Vec<Upload<'a>>,
+}
+
+/// A file uploaded with a request (as `multipart/form-data`).
+pub struct Upload<'a> {
+    /// The file's contents.
+    pub data: Box<Read + 'a>,
+    /// The file's name.
+    pub filename: Option<String>,
+    /// The file's content type.
+    pub mime: Option<Mime>,
+}
+
+/// A response from the IPFS API.
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The response headers.
+    pub headers: Headers,
+    /// The response body.
+    pub body: Box<Read + Send>,
+}
+
+/// A way of sending requests to the IPFS API.
+pub trait Transport: Send + Sync {
+    /// Send a request, returning the response.
+    ///
+    /// Unsuccessful responses (non-2xx status codes) should be returned as
+    /// responses, not errors: the body is parsed for the IPFS error message.
+    fn send(&self, request: Request) -> Result<Response>;
+}
+
+impl<C, S> Transport for Pool<C>
+    where C: NetworkConnector<Stream=S> + Send + Sync,
+          S: NetworkStream + Send
+{
+    fn send(&self, request: Request) -> Result<Response> {
+        let mut req = HttpRequest::with_connector(request.method, request.url, self)?;
+        req.set_read_timeout(request.timeout)?;
+        req.set_write_timeout(request.timeout)?;
+        for header in request.headers.iter() {
+            req.headers_mut().set_raw(header.name().to_owned(), vec![header.value_string().into_bytes()]);
+        }
+        let resp = if request.uploads.is_empty() {
+            req.start()?.send()?
+        } else {
+            let mut req = Multipart::from_request(req)?;
+            for mut upload in request.uploads {
+                req.write_stream("data", &mut upload.data, upload.filename.as_ref().map(|f| &f[..]), upload.mime)?;
+            }
+            req.send()?
+        };
+        Ok(Response {
+            status: resp.status.to_u16(),
+            headers: resp.headers.clone(),
+            body: Box::new(resp),
+        })
+    }
+}
@@ -0,0 +1,63 @@
+//! Support for talking to the IPFS API over a unix domain socket.
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use hyper;
+use hyper::net::{NetworkConnector, NetworkStream};
+
+/// Connects to a unix socket regardless of the requested host and port.
+pub struct UnixConnector(pub PathBuf);
+
+impl NetworkConnector for UnixConnector {
+    type Stream = UnixHttpStream;
+
+    fn connect(&self, _: &str, _: u16, scheme: &str) -> hyper::Result<UnixHttpStream> {
+        if scheme != "http" {
+            return Err(hyper::Error::Io(io::Error::new(io::ErrorKind::InvalidInput,
+                                                       "unsupported scheme for a unix socket")));
+        }
+        Ok(UnixHttpStream(UnixStream::connect(&self.0)?))
+    }
+}
+
+pub struct UnixHttpStream(UnixStream);
+
+impl Read for UnixHttpStream {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for UnixHttpStream {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl NetworkStream for UnixHttpStream {
+    fn peer_addr(&mut self) -> io::Result<SocketAddr> {
+        Err(io::Error::new(io::ErrorKind::Other, "unix sockets don't have socket addresses"))
+    }
+
+    fn set_read_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(dur)
+    }
+
+    fn set_write_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.0.set_write_timeout(dur)
+    }
+
+    fn close(&mut self, how: Shutdown) -> io::Result<()> {
+        self.0.shutdown(how)
+    }
+}
@@ -0,0 +1,115 @@
+//! API for working with UnixFS files and directories.
+use std::io;
+
+use serde;
+
+use api::{self, Client};
+use encoding::Json;
+use unixfs_pb::Data_DataType as DataType;
+
+/// The type of a directory entry.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EntryKind {
+    /// A directory.
+    Directory,
+    /// A file.
+    File,
+    /// A symbolic link.
+    Symlink,
+    /// Some other type of entry (e.g. raw data).
+    Other(u64),
+}
+
+impl serde::Deserialize for EntryKind {
+    fn deserialize<D: serde::Deserializer>(d: &mut D) -> Result<EntryKind, D::Error> {
+        struct Visitor;
+        impl serde::de::Visitor for Visitor {
+            type Value = EntryKind;
+            fn visit_u64<E: serde::de::Error>(&mut self, v: u64) -> Result<EntryKind, E> {
+                Ok(match v {
+                    1 | 5 => EntryKind::Directory,
+                    2 => EntryKind::File,
+                    4 => EntryKind::Symlink,
+                    v => EntryKind::Other(v),
+                })
+            }
+        }
+        d.deserialize_u64(Visitor)
+    }
+}
+
+/// The type of a UnixFS node.
+///
+/// Returned from [Object::unixfs_type](../object/struct.Object.html#method.unixfs_type).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UnixFsType {
+    /// Raw file data (a leaf of a file).
+    Raw,
+    /// A directory.
+    Directory,
+    /// A file.
+    File,
+    /// File metadata.
+    Metadata,
+    /// A symbolic link.
+    Symlink,
+    /// A shard of a large (sharded) directory.
+    HamtShard,
+}
+
+impl From<DataType> for UnixFsType {
+    fn from(t: DataType) -> UnixFsType {
+        match t {
+            DataType::Raw => UnixFsType::Raw,
+            DataType::Directory => UnixFsType::Directory,
+            DataType::File => UnixFsType::File,
+            DataType::Metadata => UnixFsType::Metadata,
+            DataType::Symlink => UnixFsType::Symlink,
+            DataType::HAMTShard => UnixFsType::HamtShard,
+        }
+    }
+}
+
+/// An entry in a UnixFS directory.
+///
+/// Returned from [ls](fn.ls.html).
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct DirEntry {
+    /// The entry's name.
+    #[serde(rename="Name")]
+    pub name: String,
+    /// The entry's hash.
+    #[serde(rename="Hash")]
+    pub hash: String,
+    /// The entry's size.
+    #[serde(rename="Size")]
+    pub size: u64,
+    /// The entry's type.
+    #[serde(rename="Type")]
+    pub kind: EntryKind,
+}
+
+/// List the entries in a UnixFS directory.
+pub fn ls(path: &str) -> io::Result<Vec<DirEntry>> {
+    api::default_client().ls(path)
+}
+
+impl Client {
+    /// List the entries in a UnixFS directory. See [unixfs::ls](unixfs/fn.ls.html).
+    pub fn ls(&self, path: &str) -> io::Result<Vec<DirEntry>> {
+        #[derive(Deserialize)]
+        struct LsResult {
+            #[serde(rename="Objects")]
+            objects: Vec<LsObject>,
+        }
+
+        #[derive(Deserialize)]
+        struct LsObject {
+            #[serde(rename="Links", default)]
+            links: Vec<DirEntry>,
+        }
+
+        let resp = api::get::<Json, LsResult>(self, "ls", &[("arg", path)])?;
+        Ok(resp.objects.into_iter().next().map(|o| o.links).unwrap_or_else(Vec::new))
+    }
+}
@@ -0,0 +1,494 @@
+// This file is generated. Do not edit
+
+#![allow(dead_code)]
+#![allow(non_camel_case_types)]
+#![allow(non_upper_case_globals)]
+#![allow(unused_imports)]
+
+use protobuf::Message as Message_imported_for_functions;
+use protobuf::ProtobufEnum as ProtobufEnum_imported_for_functions;
+
+#[derive(Clone,Default)]
+pub struct Data {
+    // message fields
+    Type: ::std::option::Option<Data_DataType>,
+    Data: ::protobuf::SingularField<::std::vec::Vec<u8>>,
+    filesize: ::std::option::Option<u64>,
+    blocksizes: ::std::vec::Vec<u64>,
+    hashType: ::std::option::Option<u64>,
+    fanout: ::std::option::Option<u64>,
+    // special fields
+    unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::std::cell::Cell<u32>,
+}
+
+impl Data {
+    pub fn new() -> Data {
+        ::std::default::Default::default()
+    }
+
+    pub fn default_instance() -> &'static Data {
+        static mut instance: ::protobuf::lazy::Lazy<Data> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Data,
+        };
+        unsafe {
+            instance.get(|| {
+                Data {
+                    Type: ::std::option::Option::None,
+                    Data: ::protobuf::SingularField::none(),
+                    filesize: ::std::option::Option::None,
+                    blocksizes: ::std::vec::Vec::new(),
+                    hashType: ::std::option::Option::None,
+                    fanout: ::std::option::Option::None,
+                    unknown_fields: ::protobuf::UnknownFields::new(),
+                    cached_size: ::std::cell::Cell::new(0),
+                }
+            })
+        }
+    }
+
+    // required .unixfs.pb.Data.DataType Type = 1;
+
+    pub fn clear_Type(&mut self) {
+        self.Type = ::std::option::Option::None;
+    }
+
+    pub fn has_Type(&self) -> bool {
+        self.Type.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_Type(&mut self, v: Data_DataType) {
+        self.Type = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_Type<'a>(&self) -> Data_DataType {
+        self.Type.unwrap_or(Data_DataType::Raw)
+    }
+
+    // optional bytes Data = 2;
+
+    pub fn clear_Data(&mut self) {
+        self.Data.clear();
+    }
+
+    pub fn has_Data(&self) -> bool {
+        self.Data.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_Data(&mut self, v: ::std::vec::Vec<u8>) {
+        self.Data = ::protobuf::SingularField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_Data<'a>(&'a mut self) -> &'a mut ::std::vec::Vec<u8> {
+        if self.Data.is_none() {
+            self.Data.set_default();
+        };
+        self.Data.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_Data(&mut self) -> ::std::vec::Vec<u8> {
+        self.Data.take().unwrap_or_else(|| ::std::vec::Vec::new())
+    }
+
+    pub fn get_Data<'a>(&'a self) -> &'a [u8] {
+        match self.Data.as_ref() {
+            Some(v) => &v,
+            None => &[],
+        }
+    }
+
+    // optional uint64 filesize = 3;
+
+    pub fn clear_filesize(&mut self) {
+        self.filesize = ::std::option::Option::None;
+    }
+
+    pub fn has_filesize(&self) -> bool {
+        self.filesize.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_filesize(&mut self, v: u64) {
+        self.filesize = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_filesize<'a>(&self) -> u64 {
+        self.filesize.unwrap_or(0)
+    }
+
+    // repeated uint64 blocksizes = 4;
+
+    pub fn clear_blocksizes(&mut self) {
+        self.blocksizes.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_blocksizes(&mut self, v: ::std::vec::Vec<u64>) {
+        self.blocksizes = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_blocksizes<'a>(&'a mut self) -> &'a mut ::std::vec::Vec<u64> {
+        &mut self.blocksizes
+    }
+
+    // Take field
+    pub fn take_blocksizes(&mut self) -> ::std::vec::Vec<u64> {
+        ::std::mem::replace(&mut self.blocksizes, ::std::vec::Vec::new())
+    }
+
+    pub fn get_blocksizes<'a>(&'a self) -> &'a [u64] {
+        &self.blocksizes
+    }
+
+    // optional uint64 hashType = 5;
+
+    pub fn clear_hashType(&mut self) {
+        self.hashType = ::std::option::Option::None;
+    }
+
+    pub fn has_hashType(&self) -> bool {
+        self.hashType.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_hashType(&mut self, v: u64) {
+        self.hashType = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_hashType<'a>(&self) -> u64 {
+        self.hashType.unwrap_or(0)
+    }
+
+    // optional uint64 fanout = 6;
+
+    pub fn clear_fanout(&mut self) {
+        self.fanout = ::std::option::Option::None;
+    }
+
+    pub fn has_fanout(&self) -> bool {
+        self.fanout.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_fanout(&mut self, v: u64) {
+        self.fanout = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_fanout<'a>(&self) -> u64 {
+        self.fanout.unwrap_or(0)
+    }
+}
+
+impl ::protobuf::Message for Data {
+    fn is_initialized(&self) -> bool {
+        if self.Type.is_none() {
+            return false;
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !try!(is.eof()) {
+            let (field_number, wire_type) = try!(is.read_tag_unpack());
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_enum());
+                    self.Type = ::std::option::Option::Some(tmp);
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = self.Data.set_default();
+                    try!(is.read_bytes_into(tmp))
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_uint64());
+                    self.filesize = ::std::option::Option::Some(tmp);
+                },
+                4 => {
+                    try!(::protobuf::rt::read_repeated_uint64_into(wire_type, is, &mut self.blocksizes));
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_uint64());
+                    self.hashType = ::std::option::Option::Some(tmp);
+                },
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_uint64());
+                    self.fanout = ::std::option::Option::Some(tmp);
+                },
+                _ => {
+                    let unknown = try!(is.read_unknown(wire_type));
+                    self.mut_unknown_fields().add_value(field_number, unknown);
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in self.Type.iter() {
+            my_size += ::protobuf::rt::enum_size(1, *value);
+        };
+        for value in self.Data.iter() {
+            my_size += ::protobuf::rt::bytes_size(2, &value);
+        };
+        for value in self.filesize.iter() {
+            my_size += ::protobuf::rt::value_size(3, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        for value in self.blocksizes.iter() {
+            my_size += ::protobuf::rt::value_size(4, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        for value in self.hashType.iter() {
+            my_size += ::protobuf::rt::value_size(5, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        for value in self.fanout.iter() {
+            my_size += ::protobuf::rt::value_size(6, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if let Some(v) = self.Type {
+            try!(os.write_enum(1, v.value()));
+        };
+        if let Some(v) = self.Data.as_ref() {
+            try!(os.write_bytes(2, &v));
+        };
+        if let Some(v) = self.filesize {
+            try!(os.write_uint64(3, v));
+        };
+        for v in self.blocksizes.iter() {
+            try!(os.write_uint64(4, *v));
+        };
+        if let Some(v) = self.hashType {
+            try!(os.write_uint64(5, v));
+        };
+        if let Some(v) = self.fanout {
+            try!(os.write_uint64(6, v));
+        };
+        try!(os.write_unknown_fields(self.get_unknown_fields()));
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields<'s>(&'s self) -> &'s ::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields<'s>(&'s mut self) -> &'s mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn type_id(&self) -> ::std::any::TypeId {
+        ::std::any::TypeId::of::<Data>()
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        ::protobuf::MessageStatic::descriptor_static(None::<Self>)
+    }
+}
+
+impl ::protobuf::MessageStatic for Data {
+    fn new() -> Data {
+        Data::new()
+    }
+
+    fn descriptor_static(_: ::std::option::Option<Data>) -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_singular_enum_accessor(
+                    "Type",
+                    Data::has_Type,
+                    Data::get_Type,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_bytes_accessor(
+                    "Data",
+                    Data::has_Data,
+                    Data::get_Data,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_u64_accessor(
+                    "filesize",
+                    Data::has_filesize,
+                    Data::get_filesize,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_u64_accessor(
+                    "blocksizes",
+                    Data::get_blocksizes,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_u64_accessor(
+                    "hashType",
+                    Data::has_hashType,
+                    Data::get_hashType,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_u64_accessor(
+                    "fanout",
+                    Data::has_fanout,
+                    Data::get_fanout,
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Data>(
+                    "Data",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+}
+
+impl ::protobuf::Clear for Data {
+    fn clear(&mut self) {
+        self.clear_Type();
+        self.clear_Data();
+        self.clear_filesize();
+        self.clear_blocksizes();
+        self.clear_hashType();
+        self.clear_fanout();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::cmp::PartialEq for Data {
+    fn eq(&self, other: &Data) -> bool {
+        self.Type == other.Type &&
+        self.Data == other.Data &&
+        self.filesize == other.filesize &&
+        self.blocksizes == other.blocksizes &&
+        self.hashType == other.hashType &&
+        self.fanout == other.fanout &&
+        self.unknown_fields == other.unknown_fields
+    }
+}
+
+impl ::std::fmt::Debug for Data {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum Data_DataType {
+    Raw = 0,
+    Directory = 1,
+    File = 2,
+    Metadata = 3,
+    Symlink = 4,
+    HAMTShard = 5,
+}
+
+impl ::protobuf::ProtobufEnum for Data_DataType {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<Data_DataType> {
+        match value {
+            0 => ::std::option::Option::Some(Data_DataType::Raw),
+            1 => ::std::option::Option::Some(Data_DataType::Directory),
+            2 => ::std::option::Option::Some(Data_DataType::File),
+            3 => ::std::option::Option::Some(Data_DataType::Metadata),
+            4 => ::std::option::Option::Some(Data_DataType::Symlink),
+            5 => ::std::option::Option::Some(Data_DataType::HAMTShard),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [Data_DataType] = &[
+            Data_DataType::Raw,
+            Data_DataType::Directory,
+            Data_DataType::File,
+            Data_DataType::Metadata,
+            Data_DataType::Symlink,
+            Data_DataType::HAMTShard,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static(_: Option<Data_DataType>) -> &'static ::protobuf::reflect::EnumDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::EnumDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::EnumDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                ::protobuf::reflect::EnumDescriptor::new("Data_DataType", file_descriptor_proto())
+            })
+        }
+    }
+}
+
+impl ::std::marker::Copy for Data_DataType {
+}
+
+static file_descriptor_proto_data: &'static [u8] = &[
+    0x0a, 0x12, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x2f, 0x75, 0x6e, 0x69, 0x78, 0x66, 0x73, 0x2e, 0x70,
+    0x72, 0x6f, 0x74, 0x6f, 0x12, 0x09, 0x75, 0x6e, 0x69, 0x78, 0x66, 0x73, 0x2e, 0x70, 0x62, 0x22,
+    0xdc, 0x01, 0x0a, 0x04, 0x44, 0x61, 0x74, 0x61, 0x12, 0x26, 0x0a, 0x04, 0x54, 0x79, 0x70, 0x65,
+    0x18, 0x01, 0x20, 0x02, 0x28, 0x0e, 0x32, 0x18, 0x2e, 0x75, 0x6e, 0x69, 0x78, 0x66, 0x73, 0x2e,
+    0x70, 0x62, 0x2e, 0x44, 0x61, 0x74, 0x61, 0x2e, 0x44, 0x61, 0x74, 0x61, 0x54, 0x79, 0x70, 0x65,
+    0x12, 0x0c, 0x0a, 0x04, 0x44, 0x61, 0x74, 0x61, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0c, 0x12, 0x10,
+    0x0a, 0x08, 0x66, 0x69, 0x6c, 0x65, 0x73, 0x69, 0x7a, 0x65, 0x18, 0x03, 0x20, 0x01, 0x28, 0x04,
+    0x12, 0x12, 0x0a, 0x0a, 0x62, 0x6c, 0x6f, 0x63, 0x6b, 0x73, 0x69, 0x7a, 0x65, 0x73, 0x18, 0x04,
+    0x20, 0x03, 0x28, 0x04, 0x12, 0x10, 0x0a, 0x08, 0x68, 0x61, 0x73, 0x68, 0x54, 0x79, 0x70, 0x65,
+    0x18, 0x05, 0x20, 0x01, 0x28, 0x04, 0x12, 0x0e, 0x0a, 0x06, 0x66, 0x61, 0x6e, 0x6f, 0x75, 0x74,
+    0x18, 0x06, 0x20, 0x01, 0x28, 0x04, 0x22, 0x56, 0x0a, 0x08, 0x44, 0x61, 0x74, 0x61, 0x54, 0x79,
+    0x70, 0x65, 0x12, 0x07, 0x0a, 0x03, 0x52, 0x61, 0x77, 0x10, 0x00, 0x12, 0x0d, 0x0a, 0x09, 0x44,
+    0x69, 0x72, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x79, 0x10, 0x01, 0x12, 0x08, 0x0a, 0x04, 0x46, 0x69,
+    0x6c, 0x65, 0x10, 0x02, 0x12, 0x0c, 0x0a, 0x08, 0x4d, 0x65, 0x74, 0x61, 0x64, 0x61, 0x74, 0x61,
+    0x10, 0x03, 0x12, 0x0b, 0x0a, 0x07, 0x53, 0x79, 0x6d, 0x6c, 0x69, 0x6e, 0x6b, 0x10, 0x04, 0x12,
+    0x0d, 0x0a, 0x09, 0x48, 0x41, 0x4d, 0x54, 0x53, 0x68, 0x61, 0x72, 0x64, 0x10, 0x05,
+];
+
+static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
+    lock: ::protobuf::lazy::ONCE_INIT,
+    ptr: 0 as *const ::protobuf::descriptor::FileDescriptorProto,
+};
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    unsafe {
+        file_descriptor_proto_lazy.get(|| {
+            parse_descriptor_proto()
+        })
+    }
+}
@@ -0,0 +1,96 @@
+//! API for querying the IPFS node's version.
+use std::io;
+use std::fmt;
+
+use api::{self, Client};
+use encoding::Json;
+
+/// A (comparable) version number.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct VersionNumber {
+    /// The major version.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch version.
+    pub patch: u32,
+}
+
+impl VersionNumber {
+    /// Create a version number.
+    pub fn new(major: u32, minor: u32, patch: u32) -> VersionNumber {
+        VersionNumber {
+            major: major,
+            minor: minor,
+            patch: patch,
+        }
+    }
+
+    /// Parse a version number such as `0.4.3` or `0.4.3-dev` (pre-release
+    /// suffixes are ignored).
+    pub fn parse(s: &str) -> Option<VersionNumber> {
+        let s = s.split('-').next().unwrap();
+        let mut parts = s.split('.').map(|p| p.parse::<u32>());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Some(VersionNumber::new(major, minor, patch))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for VersionNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The IPFS node's version.
+///
+/// Returned from [version](fn.version.html).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Version {
+    /// The node's version number.
+    pub version: VersionNumber,
+    /// The commit from which the node was built (may be empty).
+    pub commit: String,
+    /// The node's repository version.
+    pub repo: String,
+    /// The node's system (e.g. `amd64/linux`).
+    pub system: String,
+}
+
+/// Get the IPFS node's version.
+pub fn version() -> io::Result<Version> {
+    api::default_client().version()
+}
+
+impl Client {
+    /// Get the IPFS node's version. See [version::version](version/fn.version.html).
+    pub fn version(&self) -> io::Result<Version> {
+        #[derive(Deserialize)]
+        struct VersionResult {
+            #[serde(rename="Version")]
+            version: String,
+            #[serde(rename="Commit", default)]
+            commit: String,
+            #[serde(rename="Repo", default)]
+            repo: String,
+            #[serde(rename="System", default)]
+            system: String,
+        }
+
+        let resp = api::get::<Json, VersionResult>(self, "version", &[])?;
+        let version = match VersionNumber::parse(&resp.version) {
+            Some(v) => v,
+            None => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid version number")),
+        };
+        Ok(Version {
+            version: version,
+            commit: resp.commit,
+            repo: resp.repo,
+            system: resp.system,
+        })
+    }
+}
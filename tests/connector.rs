@@ -0,0 +1,32 @@
+extern crate ipfs_api as ipfs;
+extern crate hyper;
+extern crate url;
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use hyper::net::{NetworkConnector, HttpStream};
+use url::Url;
+
+struct RecordingConnector(Arc<Mutex<Vec<String>>>);
+
+impl NetworkConnector for RecordingConnector {
+    type Stream = HttpStream;
+
+    fn connect(&self, _: &str, _: u16, scheme: &str) -> hyper::Result<HttpStream> {
+        self.0.lock().unwrap().push(scheme.to_owned());
+        Err(hyper::Error::Io(io::Error::new(io::ErrorKind::ConnectionRefused, "test connector")))
+    }
+}
+
+// This lives in its own test binary because it changes the global endpoint.
+#[test]
+fn https_endpoint() {
+    let schemes = Arc::new(Mutex::new(Vec::new()));
+    ipfs::set_connector(RecordingConnector(schemes.clone()));
+    ipfs::set_api_endpoint(Url::parse("https://ipfs.example.com/api/v0/").unwrap());
+
+    let err = ipfs::object::lookup("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    assert_eq!(*schemes.lock().unwrap(), vec!["https"]);
+}
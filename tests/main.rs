@@ -1,4 +1,5 @@
 extern crate ipfs_api as ipfs;
+extern crate serde_json;
 
 #[test]
 fn get_object() {
@@ -22,8 +23,904 @@ fn publish() {
         links: vec![],
     };
     let obj = obj.commit().unwrap();
-    ipfs::name::publish(&obj).unwrap();
-    // TODO: lookup my name.
-    let r = ipfs::object::lookup("/ipns/Qme6Q6RCZ7GKmpcuzKDqEtZrisygpTnyneq1N8zchdgWYq").unwrap();
+    let published = ipfs::name::publish(&obj).unwrap();
+    assert_eq!(published.name, ipfs::name::id().unwrap().id);
+    assert_eq!(published.value, obj.reference().to_string());
+    let r = ipfs::object::lookup(&format!("/ipns/{}", published.name)).unwrap();
     assert_eq!(*obj.reference(), r);
 }
+
+#[test]
+fn publish_base36() {
+    use ipfs::name::{IpnsBase, PublishOptions, NameResolveOptions};
+
+    let obj = leaf(b"publish_base36");
+    let published = ipfs::name::publish_with(&obj, &PublishOptions {
+        ipns_base: Some(IpnsBase::Base36),
+        ..Default::default()
+    }).unwrap();
+    assert!(published.name.starts_with('k'));
+
+    let path = ipfs::name::resolve_name(&format!("/ipns/{}", published.name), NameResolveOptions {
+        nocache: true,
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(path, obj.reference().to_string());
+}
+
+#[test]
+fn stat_offline() {
+    use ipfs::object::StatError;
+    match ipfs::object::stat_offline("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG") {
+        Err(StatError::NotFoundLocal) => (),
+        r => panic!("expected NotFoundLocal, got {:?}", r.map(|s| s.hash)),
+    }
+    match ipfs::object::stat_offline("not-a-hash") {
+        Err(StatError::InvalidPath(_)) => (),
+        r => panic!("expected InvalidPath, got {:?}", r.map(|s| s.hash)),
+    }
+}
+
+fn leaf(data: &[u8]) -> ipfs::object::CommittedObject {
+    ipfs::object::Object {
+        data: data.to_owned(),
+        links: vec![],
+    }.commit().unwrap()
+}
+
+fn parent(name: &str, child: &ipfs::object::CommittedObject) -> ipfs::object::CommittedObject {
+    ipfs::object::Object {
+        data: vec![],
+        links: vec![ipfs::object::Link {
+            name: name.to_owned(),
+            object: child.reference().clone(),
+        }],
+    }.commit().unwrap()
+}
+
+#[test]
+fn get_path() {
+    let c = leaf(b"c");
+    let b = parent("c", &c);
+    let a = parent("b", &b);
+    let root = parent("a", &a);
+
+    assert_eq!(root.get("a/b/c").unwrap(), c);
+    assert_eq!(root.get("a/b").unwrap(), b);
+    assert_eq!(root.get("a/").unwrap(), a);
+    assert_eq!(root.get("a").unwrap(), a);
+
+    assert_eq!(root.get("").unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(root.get("/a").unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(root.get("b").unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn cat() {
+    use std::io::Read;
+
+    // A UnixFS file node containing "testing".
+    let obj = ipfs::object::Object {
+        data: b"\x08\x02\x12\x07testing\x18\x07"[..].to_owned(),
+        links: vec![],
+    };
+    let obj = obj.commit().unwrap();
+
+    let mut contents = Vec::new();
+    ipfs::cat(obj.hash()).unwrap().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"testing");
+}
+
+#[test]
+fn add() {
+    use std::io::Read;
+
+    let r = ipfs::add(b"testing").unwrap();
+    let mut contents = Vec::new();
+    ipfs::cat(r.hash()).unwrap().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"testing");
+}
+
+#[test]
+fn links() {
+    let child = leaf(b"child");
+    let root = parent("child", &child);
+    assert_eq!(ipfs::object::links(root.hash()).unwrap(), root.links);
+}
+
+#[test]
+fn pinned() {
+    use ipfs::pin::PinType;
+
+    let obj = leaf(b"pinned");
+    obj.pin(false).unwrap();
+    let pins = ipfs::pin::pinned(PinType::Direct).unwrap();
+    assert!(pins.iter().any(|&(ref hash, t)| hash == obj.hash() && t == PinType::Direct));
+    obj.unpin(false).unwrap();
+}
+
+#[test]
+fn pin_named() {
+    use ipfs::pin::PinType;
+
+    let obj = leaf(b"pin_named");
+    obj.pin_named(true, "rust-ipfs-api archive").unwrap();
+    let pins = ipfs::pin::pinned_with_names(PinType::Recursive).unwrap();
+    let name = pins.into_iter()
+        .find(|&(ref hash, _, _)| hash == obj.hash())
+        .map(|(_, _, name)| name)
+        .expect("not pinned");
+    assert_eq!(name, Some("rust-ipfs-api archive".to_owned()));
+    obj.unpin(true).unwrap();
+}
+
+#[test]
+fn is_pinned() {
+    use ipfs::pin::PinType;
+
+    let child = leaf(b"is_pinned child");
+    let root = parent("child", &child);
+    assert_eq!(root.is_pinned().unwrap(), None);
+
+    root.pin(true).unwrap();
+    assert_eq!(root.is_pinned().unwrap(), Some(PinType::Recursive));
+    assert_eq!(child.is_pinned().unwrap(), Some(PinType::Indirect));
+
+    root.unpin(true).unwrap();
+    assert_eq!(root.is_pinned().unwrap(), None);
+}
+
+#[test]
+fn resolve_name_nocache() {
+    let obj = leaf(b"resolve_name");
+    ipfs::name::publish(&obj).unwrap();
+    let opts = ipfs::name::NameResolveOptions {
+        nocache: true,
+        ..Default::default()
+    };
+    let id = ipfs::name::id().unwrap().id;
+    let path = ipfs::name::resolve_name(&format!("/ipns/{}", id), opts).unwrap();
+    assert_eq!(path, obj.reference().to_string());
+}
+
+#[test]
+fn cid_v1() {
+    // The empty UnixFS directory.
+    let v0 = ipfs::object::Object {
+        data: b"\x08\x01"[..].to_owned(),
+        links: vec![],
+    }.commit().unwrap();
+    assert_eq!(v0.hash(), "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn");
+
+    let v1 = ipfs::object::Reference::new(
+        "bafybeiczsscdsbs7ffqz55asqdf3smv6klcw3gofszvwlyarci47bgf354".to_owned(),
+        v0.size());
+    assert_eq!(v1.get().unwrap().data, v0.data);
+
+    let root = ipfs::object::Object {
+        data: vec![],
+        links: vec![ipfs::object::Link {
+            name: "dir".to_owned(),
+            object: v1.clone(),
+        }],
+    }.commit().unwrap();
+    let root2 = ipfs::object::get(root.hash()).unwrap();
+    assert_eq!(root2.links[0].object, v1);
+}
+
+#[test]
+fn commit_invalid_link() {
+    let bad = ipfs::object::Link {
+        name: "bad".to_owned(),
+        object: ipfs::object::Reference::new("not-a-hash".to_owned(), 0),
+    };
+    let obj = ipfs::object::Object {
+        data: b"testing"[..].to_owned(),
+        links: vec![bad.clone()],
+    };
+    let err = obj.commit().unwrap_err();
+    assert_eq!(err.error.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(err.object.links, vec![bad]);
+    assert_eq!(err.object.data, b"testing");
+}
+
+#[test]
+fn refs() {
+    let c = leaf(b"refs c");
+    let b = parent("c", &c);
+    let a = parent("b", &b);
+
+    let direct = ipfs::refs::refs(a.hash(), Default::default()).unwrap();
+    assert_eq!(direct, vec![b.hash().to_owned()]);
+
+    let opts = ipfs::refs::RefsOptions {
+        recursive: true,
+        ..Default::default()
+    };
+    let all = ipfs::refs::refs(a.hash(), opts).unwrap();
+    assert_eq!(all, vec![b.hash().to_owned(), c.hash().to_owned()]);
+}
+
+#[test]
+fn client() {
+    let client = ipfs::Client::new(ipfs::get_api_endpoint());
+    let obj = client.commit(ipfs::object::Object {
+        data: b"client"[..].to_owned(),
+        links: vec![],
+    }).unwrap();
+    assert_eq!(client.get(obj.hash()).unwrap(), obj);
+    assert_eq!(ipfs::object::get(obj.hash()).unwrap(), obj);
+}
+
+#[test]
+fn block() {
+    use ipfs::block::{self, BlockFormat};
+
+    let r = block::put_block(b"raw block", BlockFormat::Raw).unwrap();
+    assert_eq!(r.size(), 9);
+    assert_eq!(block::get_block(r.hash()).unwrap(), b"raw block");
+    let stat = block::stat_block(r.hash()).unwrap();
+    assert_eq!(stat.key, r.hash());
+    assert_eq!(stat.size, 9);
+}
+
+#[test]
+fn put_block_cid_version() {
+    use ipfs::block::{self, BlockFormat, PutBlockOptions};
+
+    // The empty UnixFS directory.
+    let opts = PutBlockOptions {
+        cid_version: Some(1),
+        ..Default::default()
+    };
+    let r = block::put_block_with(b"\x0a\x02\x08\x01", BlockFormat::Protobuf, &opts).unwrap();
+    assert_eq!(r.hash(), "bafybeiczsscdsbs7ffqz55asqdf3smv6klcw3gofszvwlyarci47bgf354");
+    let r = block::put_block(b"\x0a\x02\x08\x01", BlockFormat::Protobuf).unwrap();
+    assert_eq!(r.hash(), "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn");
+}
+
+#[test]
+fn dag() {
+    use std::collections::BTreeMap;
+    use ipfs::dag::{self, DagCodec};
+
+    let mut value = BTreeMap::new();
+    value.insert("hello".to_owned(), "world".to_owned());
+    let r = dag::dag_put(&value, DagCodec::DagCbor).unwrap();
+    let value2: BTreeMap<String, String> = dag::dag_get(r.hash()).unwrap();
+    assert_eq!(value, value2);
+}
+
+#[test]
+fn dag_resolve() {
+    use std::collections::BTreeMap;
+    use ipfs::dag::{self, DagCodec};
+
+    let mut inner = BTreeMap::new();
+    inner.insert("name".to_owned(), "inner".to_owned());
+    let inner = dag::dag_put(&inner, DagCodec::DagCbor).unwrap();
+
+    let mut link = BTreeMap::new();
+    link.insert("/".to_owned(), inner.hash().to_owned());
+    let mut outer = BTreeMap::new();
+    outer.insert("inner".to_owned(), link);
+    let outer = dag::dag_put(&outer, DagCodec::DagCbor).unwrap();
+
+    let (r, rest) = dag::dag_resolve(&format!("/ipfs/{}/inner/name", outer.hash())).unwrap();
+    assert_eq!(r, inner);
+    assert_eq!(rest, "name");
+}
+
+#[test]
+fn get_links_streaming() {
+    let child = leaf(b"streamed child");
+    let root = ipfs::object::Object {
+        data: b"streamed root"[..].to_owned(),
+        links: vec![ipfs::object::Link {
+            name: "a".to_owned(),
+            object: child.reference().clone(),
+        }, ipfs::object::Link {
+            name: "b".to_owned(),
+            object: child.reference().clone(),
+        }],
+    }.commit().unwrap();
+    let links: Vec<_> = ipfs::object::get_links_streaming(root.hash()).unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(links, root.links);
+}
+
+#[test]
+fn ls() {
+    use ipfs::unixfs::EntryKind;
+
+    // A UnixFS directory containing the file "a" ("testing") and the empty
+    // directory "b".
+    let file = leaf(b"\x08\x02\x12\x07testing\x18\x07");
+    let dir = leaf(b"\x08\x01");
+    let root = ipfs::object::Object {
+        data: b"\x08\x01"[..].to_owned(),
+        links: vec![ipfs::object::Link {
+            name: "a".to_owned(),
+            object: file.reference().clone(),
+        }, ipfs::object::Link {
+            name: "b".to_owned(),
+            object: dir.reference().clone(),
+        }],
+    }.commit().unwrap();
+
+    let entries = ipfs::unixfs::ls(root.hash()).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].name, "a");
+    assert_eq!(entries[0].kind, EntryKind::File);
+    assert_eq!(entries[1].name, "b");
+    assert_eq!(entries[1].hash, dir.hash());
+    assert_eq!(entries[1].kind, EntryKind::Directory);
+}
+
+#[test]
+fn files() {
+    use std::io::Read;
+    use ipfs::files::{self, WriteOptions};
+    use ipfs::unixfs::EntryKind;
+
+    let _ = files::files_rm("/rust-ipfs-api-test", true);
+    files::files_mkdir("/rust-ipfs-api-test/dir", true).unwrap();
+    files::files_write("/rust-ipfs-api-test/dir/file", b"testing", WriteOptions {
+        create: true,
+        ..Default::default()
+    }).unwrap();
+
+    let mut contents = Vec::new();
+    files::files_read("/rust-ipfs-api-test/dir/file").unwrap().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"testing");
+
+    let stat = files::files_stat("/rust-ipfs-api-test/dir/file").unwrap();
+    assert_eq!(stat.size, 7);
+    assert_eq!(stat.kind, EntryKind::File);
+
+    files::files_mv("/rust-ipfs-api-test/dir/file", "/rust-ipfs-api-test/moved").unwrap();
+    let entries = files::files_ls("/rust-ipfs-api-test").unwrap();
+    let names: Vec<_> = entries.iter().map(|e| (&*e.name, e.kind)).collect();
+    assert_eq!(names, vec![("dir", EntryKind::Directory), ("moved", EntryKind::File)]);
+
+    files::files_rm("/rust-ipfs-api-test", true).unwrap();
+}
+
+#[test]
+fn version() {
+    use ipfs::version::VersionNumber;
+
+    assert_eq!(VersionNumber::parse("0.4.3-dev"), Some(VersionNumber::new(0, 4, 3)));
+    assert!(VersionNumber::new(0, 4, 10) < VersionNumber::new(0, 4, 11));
+    assert!(ipfs::version::version().unwrap().version > VersionNumber::new(0, 0, 0));
+}
+
+#[test]
+fn get_many() {
+    let a = leaf(b"get_many a");
+    let b = leaf(b"get_many b");
+    let bad = ipfs::object::Reference::new("not-a-hash".to_owned(), 0);
+    let refs = vec![a.reference().clone(), bad, b.reference().clone()];
+
+    let results = ipfs::object::get_many_with(&refs, 2);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().data, b"get_many a");
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap().data, b"get_many b");
+}
+
+#[test]
+fn stat_many() {
+    let a = leaf(b"stat_many a");
+    let b = parent("a", &a);
+
+    let results = ipfs::object::stat_many_with(&[a.hash(), "not-a-hash", b.hash()], 2);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().hash, a.hash());
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap().num_links, 1);
+}
+
+#[test]
+fn stat_size_only() {
+    let a = leaf(b"stat_size_only a");
+    let b = parent("a", &a);
+
+    for hash in &[a.hash(), b.hash()] {
+        let stat = ipfs::object::stat(*hash).unwrap();
+        assert_eq!(ipfs::object::stat_size_only(*hash).unwrap(), stat.cumulative_size);
+    }
+    assert_eq!(ipfs::object::stat_size_only(&format!("/ipfs/{}/a", b.hash())).unwrap(), a.size());
+    assert!(ipfs::object::stat_size_only("not-a-hash").is_err());
+}
+
+#[test]
+fn commit_verified() {
+    let a = leaf(b"commit_verified a");
+    let obj = ipfs::object::Object {
+        data: b"commit_verified b"[..].to_owned(),
+        links: vec![ipfs::object::Link { name: "a".to_owned(), object: a.reference().clone() }],
+    };
+    let verified = obj.clone().commit_verified().unwrap();
+    assert_eq!(verified, obj.commit().unwrap());
+}
+
+#[test]
+fn put_object_json() {
+    let a = leaf(b"put_object_json a");
+    let links = vec![ipfs::object::Link { name: "a".to_owned(), object: a.reference().clone() }];
+
+    let text = ipfs::object::put_object_json(b"put_object_json b"[..].to_owned(), links.clone()).unwrap();
+    let obj = ipfs::object::Object {
+        data: b"put_object_json b"[..].to_owned(),
+        links: links,
+    };
+    assert_eq!(text, obj.commit().unwrap());
+
+    let binary = ipfs::object::put_object_json(vec![0xff, 0x00, 0xfe], vec![]).unwrap();
+    assert_eq!(binary.hash(), ipfs::object::Object {
+        data: vec![0xff, 0x00, 0xfe],
+        links: vec![],
+    }.commit().unwrap().hash());
+}
+
+#[test]
+fn serialize_reference() {
+    let r = ipfs::object::Reference::new("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn".to_owned(), 4);
+    let json = serde_json::to_string(&r).unwrap();
+    assert_eq!(json, r#"{"size":4,"hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#);
+    assert_eq!(serde_json::from_str::<ipfs::object::Reference>(&json).unwrap(), r);
+}
+
+#[test]
+fn find_providers() {
+    let obj = leaf(b"find_providers");
+    let id = ipfs::name::id().unwrap().id;
+    let providers = ipfs::dht::find_providers(obj.hash(), Some(1)).unwrap();
+    assert_eq!(providers, vec![id]);
+}
+
+#[test]
+fn pubsub() {
+    let mut sub = ipfs::pubsub::subscribe("rust-ipfs-api-test").unwrap();
+    let publisher = std::thread::spawn(|| {
+        // Give the subscription time to register.
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        ipfs::pubsub::publish("rust-ipfs-api-test", b"hello").unwrap();
+    });
+    let msg = sub.next().unwrap().unwrap();
+    publisher.join().unwrap();
+
+    assert_eq!(msg.data, b"hello");
+    assert_eq!(msg.from, ipfs::name::id().unwrap().id);
+    assert_eq!(msg.topics, vec!["rust-ipfs-api-test".to_owned()]);
+}
+
+#[test]
+fn add_reader() {
+    // Big enough to span multiple chunks.
+    let data: Vec<u8> = (0..1024 * 1024).map(|i| i as u8).collect();
+    let streamed = ipfs::add_reader(std::io::Cursor::new(&data[..])).unwrap();
+    assert_eq!(streamed, ipfs::add(&data).unwrap());
+}
+
+#[test]
+fn add_file() {
+    let options = ipfs::add::AddOptions {
+        wrap_with_directory: true,
+        ..Default::default()
+    };
+    let dir = ipfs::add::add_file("hello.txt", &b"hello"[..], &options).unwrap();
+    let entries = ipfs::unixfs::ls(dir.hash()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name, "hello.txt");
+    assert_eq!(entries[0].kind, ipfs::unixfs::EntryKind::File);
+}
+
+#[test]
+fn lookup_offline() {
+    let offline = ipfs::object::StatOptions { offline: true };
+    let obj = leaf(b"lookup_offline");
+    assert_eq!(ipfs::object::lookup_with(obj.hash(), &offline).unwrap(), *obj.reference());
+
+    let err = ipfs::object::lookup_with("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", &offline).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn cat_range() {
+    use std::io::Read;
+
+    let r = ipfs::add(b"testing").unwrap();
+    let mut contents = Vec::new();
+    ipfs::cat::cat_range(r.hash(), 2, Some(3)).unwrap().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"sti");
+
+    contents.clear();
+    ipfs::cat::cat_range(r.hash(), 100, None).unwrap().read_to_end(&mut contents).unwrap();
+    assert!(contents.is_empty());
+}
+
+#[test]
+fn link_names() {
+    use ipfs::object::{Object, Reference};
+
+    let target = Reference::new("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn".to_owned(), 4);
+    let obj = Object::new().with_link("a", target.clone()).unwrap().with_link("b", target.clone()).unwrap();
+    assert_eq!(obj.links.len(), 2);
+    assert_eq!(obj.links[1].name, "b");
+
+    let mut obj = Object::new();
+    assert_eq!(obj.link("", target.clone()).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(obj.link("a/b", target).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert!(obj.links.is_empty());
+}
+
+#[test]
+fn bitswap() {
+    let stat = ipfs::bitswap::bitswap_stat().unwrap();
+    assert!(stat.dup_blks_received <= stat.blocks_received);
+    ipfs::bitswap::wantlist(None).unwrap();
+}
+
+#[test]
+fn add_hash() {
+    use std::io::Read;
+
+    let options = ipfs::add::AddOptions {
+        hash: ipfs::block::HashFunction::Blake2b256,
+        cid_version: Some(1),
+        ..Default::default()
+    };
+    let r = ipfs::add::add_with(b"add_hash", &options).unwrap();
+    // base32, CIDv1, dag-pb, blake2b-256
+    assert!(r.hash().starts_with("bafykbzace"), "unexpected cid: {}", r.hash());
+
+    let mut contents = Vec::new();
+    ipfs::cat(r.hash()).unwrap().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"add_hash");
+}
+
+#[test]
+fn children() {
+    let a = leaf(b"children a");
+    let b = leaf(b"children b");
+    let obj = ipfs::object::Object::new()
+        .with_link("a", a.reference().clone()).unwrap()
+        .with_link("b", b.reference().clone()).unwrap()
+        .commit().unwrap();
+
+    let children: Vec<_> = obj.children().map(|c| c.unwrap()).collect();
+    assert_eq!(children, vec![a, b]);
+}
+
+#[test]
+fn fetch_with_type() {
+    use std::io::Read;
+
+    let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+    let r = ipfs::add(png).unwrap();
+    let (mut reader, mime) = ipfs::cat::fetch_with_type(r.hash()).unwrap();
+    assert_eq!(mime.map(|m| m.to_string()), Some("image/png".to_owned()));
+
+    let mut contents = Vec::new();
+    reader.read_to_end(&mut contents).unwrap();
+    assert_eq!(&contents[..], &png[..]);
+}
+
+#[test]
+fn get_offline() {
+    let offline = ipfs::object::GetOptions {
+        offline: true,
+        ..Default::default()
+    };
+    let obj = leaf(b"get_offline");
+    assert_eq!(ipfs::object::get_with(obj.hash(), &offline).unwrap(), obj);
+
+    let err = ipfs::object::get_with("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", &offline).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn get_max_size() {
+    let child = leaf(&[0u8; 1024]);
+    let p = parent("big", &child);
+    let limit = ipfs::object::GetOptions {
+        max_size: Some(512),
+        ..Default::default()
+    };
+
+    let err = ipfs::object::get_with(p.hash(), &limit).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let err = p.links[0].object.get_with(&limit).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let small = leaf(b"small");
+    assert_eq!(ipfs::object::get_with(small.hash(), &limit).unwrap(), small);
+}
+
+#[test]
+fn wide_object_size() {
+    let child = leaf(b"wide_object_size");
+    let mut obj = ipfs::object::Object::new();
+    for i in 0..1000 {
+        obj.link(i.to_string(), child.reference().clone()).unwrap();
+    }
+    let obj = obj.commit().unwrap();
+    assert_eq!(obj.size(), ipfs::object::stat(obj.hash()).unwrap().cumulative_size);
+}
+
+#[test]
+fn unixfs_data() {
+    use ipfs::unixfs::UnixFsType;
+
+    let r = ipfs::add(b"testing").unwrap();
+    let obj = r.get().unwrap();
+    assert_eq!(obj.unixfs_type(), Some(UnixFsType::File));
+    assert_eq!(obj.unixfs_data().unwrap(), b"testing");
+
+    let raw = leaf(b"testing");
+    assert_eq!(raw.data_as_str(), Some("testing"));
+    assert_eq!(raw.unixfs_type(), None);
+    assert_eq!(raw.unixfs_data().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn object_diff() {
+    use ipfs::object::ChangeType;
+
+    let a = leaf(b"object_diff a");
+    let b = leaf(b"object_diff b");
+    let v1 = parent("x", &a);
+    let v2 = parent("x", &b);
+
+    let changes = ipfs::object::diff(v1.hash(), v2.hash()).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].change_type, ChangeType::Modify);
+    assert_eq!(changes[0].path, "x");
+    assert_eq!(changes[0].before.as_ref().map(|h| &h[..]), Some(a.hash()));
+    assert_eq!(changes[0].after.as_ref().map(|h| &h[..]), Some(b.hash()));
+
+    assert!(ipfs::object::diff(v1.hash(), v1.hash()).unwrap().is_empty());
+}
+
+#[test]
+fn duplicate_link_names() {
+    let a = leaf(b"duplicate_link_names a");
+    let b = leaf(b"duplicate_link_names b");
+    let obj = ipfs::object::Object::new()
+        .with_link("x", a.reference().clone()).unwrap()
+        .with_link("x", b.reference().clone()).unwrap()
+        .commit().unwrap();
+
+    let named: Vec<_> = obj.links_named("x").map(|l| l.object.hash()).collect();
+    assert_eq!(named, vec![a.hash(), b.hash()]);
+    assert_eq!(obj.links_named("y").count(), 0);
+    assert_eq!(obj.link_by_hash(b.hash()).map(|l| &l.name[..]), Some("x"));
+    assert!(obj.link_by_hash("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").is_none());
+    assert_eq!(obj.get("x").unwrap(), a);
+}
+
+#[test]
+fn files_flush() {
+    use ipfs::files::{self, WriteOptions};
+
+    let _ = files::files_rm("/rust-ipfs-api-flush", true);
+    files::files_mkdir("/rust-ipfs-api-flush", true).unwrap();
+    files::files_write("/rust-ipfs-api-flush/file", b"flushed", WriteOptions {
+        create: true,
+        ..Default::default()
+    }).unwrap();
+
+    let r = files::files_flush("/rust-ipfs-api-flush").unwrap();
+    assert_eq!(r.hash(), files::files_stat("/rust-ipfs-api-flush").unwrap().hash);
+    let entries = ipfs::unixfs::ls(r.hash()).unwrap();
+    assert_eq!(entries[0].name, "file");
+    ipfs::name::publish(&r).unwrap();
+
+    files::files_rm("/rust-ipfs-api-flush", true).unwrap();
+}
+
+#[test]
+fn directory() {
+    use std::io::Read;
+    use ipfs::dir::Directory;
+
+    let a = ipfs::add(b"directory a").unwrap();
+    let b = ipfs::add(b"directory b").unwrap();
+
+    let mut dir = Directory::new();
+    dir.insert("a.txt", a.clone()).unwrap();
+    dir.insert("docs/nested/b.txt", b.clone()).unwrap();
+    assert!(dir.insert("a.txt/c.txt", b.clone()).is_err());
+    assert_eq!(dir.get("docs/nested/b.txt"), Some(&b));
+    assert_eq!(dir.get("docs"), None);
+
+    let root = dir.commit().unwrap();
+    let entries = ipfs::unixfs::ls(root.hash()).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].name, "a.txt");
+    assert_eq!(entries[1].name, "docs");
+    let mut contents = String::new();
+    ipfs::cat(&format!("{}/docs/nested/b.txt", root.hash())).unwrap().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "directory b");
+}
+
+#[test]
+fn stat_display() {
+    let obj = leaf(&[0u8; 3 * 1024]);
+    let stat = obj.stat();
+    assert_eq!(stat.human_cumulative_size(), "3.0 KiB");
+    assert_eq!(stat.to_string(), format!("{} (0 links, 3.0 KiB data, 3.0 KiB total)", obj.hash()));
+}
+
+#[test]
+fn key_lifecycle() {
+    use ipfs::key::{self, KeyType};
+
+    let _ = key::rm_key("rust-ipfs-api-key");
+    let _ = key::rm_key("rust-ipfs-api-key-renamed");
+    let k = key::gen_key("rust-ipfs-api-key", KeyType::Rsa(2048)).unwrap();
+
+    let (was, now) = key::rename_key("rust-ipfs-api-key", "rust-ipfs-api-key-renamed", false).unwrap();
+    assert_eq!(was, "rust-ipfs-api-key");
+    assert_eq!(now, "rust-ipfs-api-key-renamed");
+
+    let removed = key::rm_key("rust-ipfs-api-key-renamed").unwrap();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].id, k.id);
+    assert!(key::list_keys().unwrap().iter().all(|k| !k.name.starts_with("rust-ipfs-api-key")));
+}
+
+#[test]
+fn open_file() {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+    let r = ipfs::add(&data).unwrap();
+    let mut file = ipfs::cat::open_file(r.hash()).unwrap();
+    assert_eq!(file.len(), 1000);
+
+    let mut buf = [0u8; 10];
+    assert_eq!(file.seek(SeekFrom::Start(500)).unwrap(), 500);
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf[..], &data[500..510]);
+
+    // Backwards.
+    assert_eq!(file.seek(SeekFrom::Current(-110)).unwrap(), 400);
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf[..], &data[400..410]);
+
+    // To the end.
+    assert_eq!(file.seek(SeekFrom::End(-10)).unwrap(), 990);
+    let mut rest = Vec::new();
+    file.read_to_end(&mut rest).unwrap();
+    assert_eq!(&rest[..], &data[990..]);
+    assert_eq!(file.read(&mut buf).unwrap(), 0);
+
+    assert!(file.seek(SeekFrom::Current(-2000)).is_err());
+}
+
+#[test]
+fn get_verify_size() {
+    // Large enough to be chunked.
+    let data = vec![7u8; 1024 * 1024];
+    let root = ipfs::add(&data).unwrap().get().unwrap();
+    assert!(!root.links.is_empty());
+
+    let verify = ipfs::object::GetOptions { verify_size: true, ..Default::default() };
+    for link in &root.links {
+        link.object.get().unwrap();
+        link.object.get_with(&verify).unwrap();
+    }
+
+    // Recorded sizes are only checked on request.
+    let wrong = ipfs::object::Reference::new(root.hash().to_owned(), root.size() + 1);
+    wrong.get().unwrap();
+    let err = wrong.get_with(&verify).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn object_from_protobuf() {
+    let a = leaf(b"from_protobuf a");
+    let obj = ipfs::object::Object {
+        data: b"from_protobuf b"[..].to_owned(),
+        links: vec![ipfs::object::Link { name: "a".to_owned(), object: a.reference().clone() }],
+    };
+    let committed = obj.clone().commit().unwrap();
+    let block = ipfs::block::get_block(committed.hash()).unwrap();
+    assert_eq!(ipfs::object::Object::from_protobuf(&block).unwrap(), obj);
+
+    let err = ipfs::object::Object::from_protobuf(b"\xff\xff\xff").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn ensure_pinned() {
+    use ipfs::pin::PinType;
+
+    let obj = leaf(b"ensure_pinned");
+    obj.ensure_pinned(true).unwrap();
+    obj.ensure_pinned(true).unwrap();
+    // Directly pinning a recursively pinned object.
+    obj.ensure_pinned(false).unwrap();
+    assert_eq!(obj.is_pinned().unwrap(), Some(PinType::Recursive));
+
+    obj.ensure_unpinned(true).unwrap();
+    obj.ensure_unpinned(true).unwrap();
+    assert_eq!(obj.is_pinned().unwrap(), None);
+}
+
+#[test]
+fn tar_round_trip() {
+    use std::io::Read;
+    use ipfs::dir::Directory;
+
+    let mut dir = Directory::new();
+    dir.insert("a.txt", ipfs::add(b"tar a").unwrap()).unwrap();
+    dir.insert("sub/b.txt", ipfs::add(b"tar b").unwrap()).unwrap();
+    let root = dir.commit().unwrap();
+
+    let mut archive = Vec::new();
+    ipfs::tar::export_tar(root.hash()).unwrap().read_to_end(&mut archive).unwrap();
+    assert!(!archive.is_empty());
+
+    let imported = ipfs::tar::import_tar(&archive[..]).unwrap();
+    let mut exported = Vec::new();
+    ipfs::tar::cat_tar(imported.hash()).unwrap().read_to_end(&mut exported).unwrap();
+    assert_eq!(exported, archive);
+}
+
+#[test]
+fn files_chcid() {
+    use ipfs::files;
+
+    let _ = files::files_rm("/rust-ipfs-api-chcid", true);
+    files::files_mkdir("/rust-ipfs-api-chcid", true).unwrap();
+    let v0 = files::files_stat("/rust-ipfs-api-chcid").unwrap().hash;
+
+    files::files_chcid("/rust-ipfs-api-chcid", 1, None).unwrap();
+    let v1 = files::files_stat("/rust-ipfs-api-chcid").unwrap().hash;
+    assert_eq!(v1, ipfs::multihash::convert_cid(&v0, 1).unwrap());
+
+    files::files_rm("/rust-ipfs-api-chcid", true).unwrap();
+}
+
+#[test]
+fn stat_block_size() {
+    let child = leaf(b"stat_block_size");
+    let obj = parent("child", &child);
+    let local = obj.stat();
+    let remote = ipfs::object::stat(obj.hash()).unwrap();
+    assert_eq!(local.block_size, remote.block_size);
+    assert_eq!(local.links_size, remote.links_size);
+    assert_eq!(remote.block_size, remote.links_size + remote.data_size as u64);
+}
+
+#[test]
+fn unixfs_predicates() {
+    let file = ipfs::add(b"unixfs_predicates").unwrap().get().unwrap();
+    assert!(file.is_file());
+    assert!(!file.is_directory());
+    assert_eq!(file.file_size(), Some(17));
+
+    let big = ipfs::add(&vec![0u8; 1024 * 1024]).unwrap().get().unwrap();
+    assert!(big.is_file());
+    assert_eq!(big.file_size(), Some(1024 * 1024));
+
+    // The empty UnixFS directory.
+    let dir = ipfs::object::Object {
+        data: b"\x08\x01"[..].to_owned(),
+        links: vec![],
+    }.commit().unwrap();
+    assert!(dir.is_directory());
+    assert!(!dir.is_file());
+    assert_eq!(dir.file_size(), None);
+
+    let raw = leaf(b"not unixfs");
+    assert!(!raw.is_directory());
+    assert!(!raw.is_file());
+    assert_eq!(raw.file_size(), None);
+}
@@ -0,0 +1,206 @@
+extern crate ipfs_api as ipfs;
+extern crate url;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+use url::Url;
+
+fn respond(listener: &TcpListener, status: &str, body: &str) {
+    let (stream, _) = listener.accept().unwrap();
+    respond_on(stream, status, body);
+}
+
+fn respond_on<S: Read + Write>(stream: S, status: &str, body: &str) {
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line == "\r\n" || line.is_empty() {
+            break;
+        }
+    }
+    let mut stream = reader.into_inner();
+    write!(stream,
+           "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+           status,
+           body.len(),
+           body)
+        .unwrap();
+}
+
+#[test]
+fn retry() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let error = r#"{"Message":"temporary failure","Code":2}"#;
+        respond(&listener, "500 Internal Server Error", error);
+        respond(&listener, "500 Internal Server Error", error);
+        respond(&listener, "200 OK", r#"{"Hash":"QmTest","NumLinks":0,"DataSize":0,"CumulativeSize":0}"#);
+    });
+
+    let mut client = ipfs::Client::new(Url::parse(&format!("http://127.0.0.1:{}/api/v0/", port)).unwrap());
+    client.set_retry_policy(Some(ipfs::RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(10),
+    }));
+    assert_eq!(client.stat("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap().hash, "QmTest");
+    server.join().unwrap();
+}
+
+#[test]
+fn sized_block() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        respond(&listener, "200 OK", &String::from_utf8(vec![b'x'; 1024 * 1024]).unwrap());
+    });
+
+    let client = ipfs::Client::new(Url::parse(&format!("http://127.0.0.1:{}/api/v0/", port)).unwrap());
+    let block = client.get_block("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    assert_eq!(block.len(), 1024 * 1024);
+    // Read into a buffer sized from the Content-Length rather than grown.
+    assert_eq!(block.capacity(), block.len());
+    server.join().unwrap();
+}
+
+#[test]
+fn error_code() {
+    use ipfs::object::StatError;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        respond(&listener, "400 Bad Request", r#"{"Message":"bad request","Code":1}"#);
+    });
+
+    let client = ipfs::Client::new(Url::parse(&format!("http://127.0.0.1:{}/api/v0/", port)).unwrap());
+    match client.stat("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn") {
+        Err(StatError::Other(e)) => assert_eq!(e.code(), Some(ipfs::error::ERR_CLIENT)),
+        _ => panic!("expected an API error"),
+    }
+    server.join().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_socket() {
+    use std::os::unix::net::UnixListener;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let nonce = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+    let path = std::env::temp_dir().join(format!("rust-ipfs-api-test-{}.sock", nonce));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        respond_on(stream, "200 OK", r#"{"Hash":"QmTest","NumLinks":0,"DataSize":0,"CumulativeSize":0}"#);
+    });
+
+    let client = ipfs::Client::with_unix_socket(&path).unwrap();
+    assert_eq!(client.stat("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap().hash, "QmTest");
+    server.join().unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn connection_reuse() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        // Only accept one connection and answer both requests on it.
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        for _ in 0..2 {
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let body = r#"{"Hash":"QmTest","NumLinks":0,"DataSize":0,"CumulativeSize":0}"#;
+            write!(reader.get_mut(),
+                   "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                   body.len(),
+                   body)
+                .unwrap();
+        }
+    });
+
+    let url = Url::parse(&format!("http://127.0.0.1:{}/api/v0/", port)).unwrap();
+    let mut client = ipfs::Client::with_pool_config(url, ipfs::transport::PoolConfig { max_idle: 1 });
+    client.set_timeout(Some(Duration::from_secs(5)));
+    for _ in 0..2 {
+        assert_eq!(client.stat("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap().hash, "QmTest");
+    }
+    server.join().unwrap();
+}
+
+#[test]
+fn gateway_fallback() {
+    // Nothing listens on the API port.
+    let dead_port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let gateway = TcpListener::bind("127.0.0.1:0").unwrap();
+    let gateway_port = gateway.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        respond(&gateway, "200 OK", "hello gateway");
+        // The raw block of the empty UnixFS directory.
+        respond(&gateway, "200 OK", "\x0a\x02\x08\x01");
+    });
+
+    let mut client = ipfs::Client::new(Url::parse(&format!("http://127.0.0.1:{}/api/v0/", dead_port)).unwrap());
+    assert!(client.cat("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").is_err());
+
+    client.set_gateway_fallback(Some(Url::parse(&format!("http://127.0.0.1:{}/", gateway_port)).unwrap()));
+    let mut contents = String::new();
+    client.cat_range("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn", 6, None).unwrap().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "gateway");
+
+    let obj = client.get("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    assert_eq!(obj.data, b"\x08\x01");
+    assert_eq!(obj.size(), 4);
+    server.join().unwrap();
+}
+
+#[test]
+fn subscribe_timeout() {
+    use std::sync::mpsc;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+        }
+        let mut stream = reader.into_inner();
+        write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n").unwrap();
+        stream.flush().unwrap();
+        // Send nothing until the client gives up.
+        let _ = done_rx.recv();
+    });
+
+    let client = ipfs::Client::new(Url::parse(&format!("http://127.0.0.1:{}/api/v0/", port)).unwrap());
+    let mut sub = client.pubsub_subscribe("rust-ipfs-api-test").unwrap();
+    sub.set_read_timeout(Some(Duration::from_millis(100)));
+    match sub.next() {
+        Some(Err(ref e)) if e.kind() == std::io::ErrorKind::TimedOut => (),
+        r => panic!("expected a timeout, got {:?}", r.map(|r| r.map(|m| m.data))),
+    }
+    // Still usable after timing out.
+    assert!(sub.next().unwrap().is_err());
+
+    done_tx.send(()).unwrap();
+    server.join().unwrap();
+    assert!(sub.next().is_none());
+}
@@ -0,0 +1,1084 @@
+extern crate ipfs_api as ipfs;
+extern crate url;
+#[cfg(feature = "async")]
+extern crate futures;
+
+use std::io::{self, Cursor, Read};
+use std::sync::{Arc, Mutex};
+
+use ipfs::transport::{Transport, Request, Response, Headers};
+use url::Url;
+
+/// Maps API methods (e.g. `object/stat`) to canned responses and records the
+/// requests it receives.
+#[derive(Clone, Default)]
+struct MockTransport {
+    // (method, status, body, used only once)
+    responses: Arc<Mutex<Vec<(String, u16, String, bool)>>>,
+    requests: Arc<Mutex<Vec<(String, Vec<(String, String)>, Vec<u8>, Headers)>>>,
+    urls: Arc<Mutex<Vec<Url>>>,
+    // The (filename, content type) of every upload.
+    uploads: Arc<Mutex<Vec<(Option<String>, Option<String>)>>>,
+}
+
+impl MockTransport {
+    fn on(&self, method: &str, status: u16, body: &str) {
+        self.responses.lock().unwrap().push((method.to_owned(), status, body.to_owned(), false));
+    }
+
+    /// Like `on` but the response is only used for the next request to the
+    /// method (after any earlier `once` responses for it).
+    fn once(&self, method: &str, status: u16, body: &str) {
+        let mut responses = self.responses.lock().unwrap();
+        let pos = responses.iter().position(|r| !r.3).unwrap_or(responses.len());
+        responses.insert(pos, (method.to_owned(), status, body.to_owned(), true));
+    }
+
+    fn client(&self) -> ipfs::Client {
+        ipfs::Client::with_transport(Url::parse("http://mock/api/v0/").unwrap(), self.clone())
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: Request) -> ipfs::error::Result<Response> {
+        let path = request.url.serialize_path().unwrap();
+        let method = path.trim_left_matches("/api/v0/").to_owned();
+        let args = request.url.query_pairs().unwrap_or_else(Vec::new);
+        let mut data = Vec::new();
+        for mut upload in request.uploads {
+            upload.data.read_to_end(&mut data)?;
+            self.uploads.lock().unwrap().push((upload.filename, upload.mime.map(|m| m.to_string())));
+        }
+        self.requests.lock().unwrap().push((method.clone(), args, data, request.headers.clone()));
+        self.urls.lock().unwrap().push(request.url.clone());
+
+        let mut responses = self.responses.lock().unwrap();
+        let (status, body) = match responses.iter().position(|r| r.0 == method) {
+            Some(i) if responses[i].3 => {
+                let (_, status, body, _) = responses.remove(i);
+                (status, body)
+            }
+            Some(i) => (responses[i].1, responses[i].2.clone()),
+            None => (404, "404 page not found".to_owned()),
+        };
+        Ok(Response {
+            status: status,
+            headers: Headers::new(),
+            body: Box::new(Cursor::new(body.into_bytes())),
+        })
+    }
+}
+
+#[test]
+fn stat() {
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmTest","NumLinks":2,"DataSize":3,"CumulativeSize":100}"#);
+
+    let r = mock.client().lookup("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    assert_eq!(r.hash(), "QmTest");
+    assert_eq!(r.size(), 100);
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].0, "object/stat");
+    assert!(requests[0].1.contains(&("arg".to_owned(), "/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn".to_owned())));
+}
+
+#[test]
+fn stat_block_size() {
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmTest","NumLinks":2,"DataSize":3,"CumulativeSize":100,"BlockSize":93,"LinksSize":90}"#);
+
+    let stat = mock.client().stat("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    assert_eq!(stat.block_size, 93);
+    assert_eq!(stat.links_size, 90);
+}
+
+#[test]
+fn api_error() {
+    let mock = MockTransport::default();
+    mock.on("object/stat", 500, r#"{"Message":"invalid ipfs ref path","Code":0}"#);
+
+    match mock.client().stat("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn/missing") {
+        Err(ipfs::object::StatError::InvalidPath(_)) => (),
+        r => panic!("expected InvalidPath, got {:?}", r.map(|s| s.hash)),
+    }
+    assert_eq!(mock.requests.lock().unwrap()[0].0, "object/stat");
+}
+
+#[test]
+fn swarm_peers() {
+    use ipfs::swarm::Direction;
+
+    let mock = MockTransport::default();
+    mock.on("swarm/peers", 200, r#"{"Peers":[
+        {"Addr":"/ip4/1.2.3.4/tcp/4001","Peer":"QmPeer","Latency":"12ms","Direction":2},
+        {"Addr":"/ip4/5.6.7.8/tcp/4001","Peer":"QmOther"}
+    ]}"#);
+
+    let peers = mock.client().swarm_peers().unwrap();
+    assert_eq!(peers.len(), 2);
+    assert_eq!(peers[0].latency, Some("12ms".to_owned()));
+    assert_eq!(peers[0].direction, Some(Direction::Outbound));
+    assert_eq!(peers[1].peer, "QmOther");
+    assert_eq!(peers[1].direction, None);
+}
+
+#[test]
+fn swarm_addrs_and_filters() {
+    let mock = MockTransport::default();
+    mock.on("swarm/addrs", 200, r#"{"Addrs":{
+        "QmPeer":["/ip4/1.2.3.4/tcp/4001","/ip6/::1/tcp/4001"],
+        "QmOther":[]
+    }}"#);
+    mock.on("swarm/filters", 200, r#"{"Strings":["/ip4/10.0.0.0/ipcidr/8"]}"#);
+    mock.on("swarm/filters/add", 200, r#"{"Strings":["/ip4/192.168.0.0/ipcidr/16"]}"#);
+
+    let client = mock.client();
+    let addrs = client.swarm_addrs().unwrap();
+    assert_eq!(addrs.len(), 2);
+    assert_eq!(addrs["QmPeer"], vec!["/ip4/1.2.3.4/tcp/4001".to_owned(), "/ip6/::1/tcp/4001".to_owned()]);
+    assert!(addrs["QmOther"].is_empty());
+
+    assert_eq!(client.swarm_filters().unwrap(), vec!["/ip4/10.0.0.0/ipcidr/8".to_owned()]);
+    client.swarm_add_filter("/ip4/192.168.0.0/ipcidr/16").unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests[2].0, "swarm/filters/add");
+    assert!(requests[2].1.contains(&("arg".to_owned(), "/ip4/192.168.0.0/ipcidr/16".to_owned())));
+}
+
+#[test]
+fn swarm_connect() {
+    let mock = MockTransport::default();
+    // The word "failure" elsewhere in the string doesn't make it a failure.
+    mock.on("swarm/connect", 200, r#"{"Strings":["connect QmPeer success: retried after dial failure"]}"#);
+    mock.on("swarm/disconnect", 200, r#"{"Strings":["disconnect QmPeer failure: conn not found"]}"#);
+
+    let client = mock.client();
+    client.swarm_connect("/ip4/1.2.3.4/tcp/4001/ipfs/QmPeer").unwrap();
+    let err = client.swarm_disconnect("/ip4/1.2.3.4/tcp/4001/ipfs/QmPeer").unwrap_err();
+    assert_eq!(err.to_string(), "disconnect QmPeer failure: conn not found");
+}
+
+#[test]
+fn upload() {
+    let mock = MockTransport::default();
+    mock.on("add", 200, r#"{"Name":"hello.txt","Hash":"QmFile","Size":"13"}
+{"Name":"","Hash":"QmDir","Size":"65"}
+"#);
+
+    let r = mock.client().add_file("hello.txt", &b"hello"[..], &Default::default()).unwrap();
+    assert_eq!(r.hash(), "QmDir");
+    assert_eq!(r.size(), 65);
+    assert_eq!(mock.requests.lock().unwrap()[0].2, b"hello");
+}
+
+#[test]
+fn keep_alive_uploads() {
+    let mock = MockTransport::default();
+    mock.on("add", 200, r#"{"Name":"QmFile","Hash":"QmFile","Size":"13"}"#);
+    let mut client = mock.client();
+
+    client.add(b"hello").unwrap();
+    client.set_keep_alive_uploads(true);
+    client.add(b"hello").unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests[0].3.get_raw("Connection"), Some(&[b"close".to_vec()][..]));
+    assert_eq!(requests[1].3.get_raw("Connection"), None);
+}
+
+#[test]
+fn user_agent() {
+    let mock = MockTransport::default();
+    mock.on("version", 200, r#"{"Version":"0.4.4","Commit":"","Repo":"4","System":"amd64/linux","Golang":"go1.7"}"#);
+    let mut client = mock.client();
+
+    client.version().unwrap();
+    client.set_user_agent("my-app/1.0");
+    client.version().unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(ipfs::DEFAULT_USER_AGENT.starts_with("rust-ipfs-api/"));
+    assert_eq!(requests[0].3.get_raw("User-Agent"), Some(&[ipfs::DEFAULT_USER_AGENT.as_bytes().to_vec()][..]));
+    assert_eq!(requests[1].3.get_raw("User-Agent"), Some(&[b"my-app/1.0".to_vec()][..]));
+}
+
+#[test]
+fn unknown_method() {
+    let mock = MockTransport::default();
+    let err = mock.client().get_block("QmTest").unwrap_err();
+    match err.get_ref().and_then(|e| e.downcast_ref::<ipfs::Error>()) {
+        Some(&ipfs::Error::Status { status: 404, .. }) => (),
+        e => panic!("expected a 404 status error, got {:?}", e),
+    }
+}
+
+#[test]
+fn resolve_dnslink() {
+    let mock = MockTransport::default();
+    mock.on("resolve", 200, r#"{"Path":"/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#);
+    let path = mock.client().resolve_dnslink("example.com").unwrap();
+    assert_eq!(path, "/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn");
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[0].1.contains(&("arg".to_owned(), "/ipns/example.com".to_owned())));
+    assert!(requests[0].1.contains(&("recursive".to_owned(), "true".to_owned())));
+}
+
+#[test]
+fn resolve_dnslink_not_ipfs() {
+    let mock = MockTransport::default();
+    mock.on("resolve", 200, r#"{"Path":"/ipns/other.example.com"}"#);
+    let err = mock.client().resolve_dnslink("/ipns/example.com").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn config() {
+    let mock = MockTransport::default();
+    mock.on("config", 200, r#"{"Key":"Datastore.GCPeriod","Value":"1h"}"#);
+    let client = mock.client();
+
+    let value = client.get_config("Datastore.GCPeriod").unwrap();
+    assert_eq!(value.as_string(), Some("1h"));
+    client.set_config("Datastore.GCPeriod", &value, false).unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    let args = |i: usize| -> Vec<String> {
+        requests[i].1.iter().filter(|a| a.0 == "arg").map(|a| a.1.clone()).collect()
+    };
+    assert_eq!(args(0), vec!["Datastore.GCPeriod"]);
+    assert_eq!(args(1), vec!["Datastore.GCPeriod", "1h"]);
+}
+
+#[test]
+fn auth() {
+    let mock = MockTransport::default();
+    mock.on("id", 200, r#"{"ID":"QmPeer","PublicKey":"","Addresses":null,"AgentVersion":"","ProtocolVersion":""}"#);
+    let mut client = mock.client();
+
+    client.id().unwrap();
+    client.set_auth(Some(ipfs::Auth::Bearer("secret".to_owned())));
+    client.id().unwrap();
+    client.set_auth(Some(ipfs::Auth::Basic {
+        username: "user".to_owned(),
+        password: Some("pass".to_owned()),
+    }));
+    client.id().unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    let auth = |i: usize| requests[i].3.get_raw("Authorization").map(|v| v[0].clone());
+    assert_eq!(auth(0), None);
+    assert_eq!(auth(1), Some(b"Bearer secret".to_vec()));
+    assert_eq!(auth(2), Some(b"Basic dXNlcjpwYXNz".to_vec()));
+}
+
+#[test]
+fn add_path() {
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let nonce = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+    let root = std::env::temp_dir().join(format!("rust-ipfs-api-test-{}", nonce));
+    fs::create_dir_all(root.join("a")).unwrap();
+    fs::create_dir(root.join("empty")).unwrap();
+    File::create(root.join("a").join("b.txt")).unwrap().write_all(b"hello ").unwrap();
+    File::create(root.join("c.txt")).unwrap().write_all(b"world").unwrap();
+
+    let mock = MockTransport::default();
+    mock.on("add", 200, r#"{"Name":"root/a/b.txt","Hash":"QmB","Size":"14"}
+{"Name":"root/c.txt","Hash":"QmC","Size":"13"}
+{"Name":"root/empty","Hash":"QmEmpty","Size":"4"}
+{"Name":"root","Hash":"QmRoot","Size":"120"}
+{"Name":"","Hash":"QmWrap","Size":"175"}
+"#);
+    let r = mock.client().add_path(&root, &Default::default());
+    let all = mock.client().add_path_all(&root, &Default::default());
+    fs::remove_dir_all(&root).unwrap();
+    assert_eq!(r.unwrap().hash(), "QmWrap");
+
+    let all = all.unwrap();
+    let names: Vec<_> = all.iter().map(|&(ref name, ref r)| (&name[..], r.hash())).collect();
+    assert_eq!(names, vec![("root/a/b.txt", "QmB"),
+                           ("root/c.txt", "QmC"),
+                           ("root/empty", "QmEmpty"),
+                           ("root", "QmRoot"),
+                           ("", "QmWrap")]);
+    assert_eq!(all[1].1.size(), 13);
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests[0].2, b"hello world");
+    assert!(requests[0].1.contains(&("recursive".to_owned(), "true".to_owned())));
+    assert!(requests[0].1.contains(&("wrap-with-directory".to_owned(), "true".to_owned())));
+
+    // The empty directory is uploaded as a directory part with no contents.
+    let name = root.file_name().unwrap().to_str().unwrap();
+    let uploads = mock.uploads.lock().unwrap();
+    let empty = (Some(format!("{}/empty", name)), Some("application/x-directory".to_owned()));
+    assert!(uploads.contains(&empty));
+}
+
+#[cfg(unix)]
+#[test]
+fn add_path_symlinks() {
+    use std::fs::{self, File};
+    use std::os::unix::fs::symlink;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let nonce = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+    let root = std::env::temp_dir().join(format!("rust-ipfs-api-symlinks-{}", nonce));
+    fs::create_dir(&root).unwrap();
+    File::create(root.join("file.txt")).unwrap();
+    symlink(root.join("file.txt"), root.join("link")).unwrap();
+
+    let mock = MockTransport::default();
+    mock.on("add", 200, r#"{"Name":"","Hash":"QmWrap","Size":"100"}"#);
+    let r = mock.client().add_path(&root, &Default::default());
+    let link = mock.client().add_path(&root.join("link"), &Default::default());
+    fs::remove_dir_all(&root).unwrap();
+    r.unwrap();
+
+    // Links inside the directory are skipped...
+    let name = root.file_name().unwrap().to_str().unwrap().to_owned();
+    let uploads: Vec<_> = mock.uploads.lock().unwrap().iter().map(|u| u.0.clone().unwrap()).collect();
+    assert_eq!(uploads, vec![name.clone(), format!("{}/file.txt", name)]);
+    // ...and adding a link itself fails without making a request.
+    assert_eq!(link.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(mock.requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn add_progress() {
+    let mock = MockTransport::default();
+    mock.on("add", 200, r#"{"Name":"","Bytes":262144}
+{"Name":"","Bytes":300000}
+{"Name":"QmFile","Hash":"QmFile","Size":"300014"}
+"#);
+
+    let mut progress = Vec::new();
+    let data = vec![0u8; 300000];
+    let r = mock.client().add_reader_with_progress(&data[..], &Default::default(), |b| progress.push(b)).unwrap();
+    assert_eq!(r.hash(), "QmFile");
+    assert_eq!(progress, vec![262144, 300000]);
+    assert!(mock.requests.lock().unwrap()[0].1.contains(&("progress".to_owned(), "true".to_owned())));
+}
+
+#[test]
+fn bandwidth() {
+    use ipfs::stats::BandwidthOptions;
+
+    let mock = MockTransport::default();
+    mock.on("stats/bw", 200, r#"{"TotalIn":1024,"TotalOut":2048,"RateIn":12.5,"RateOut":0}"#);
+    let bw = mock.client().bandwidth(BandwidthOptions {
+        peer: Some("QmPeer"),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(bw.total_in, 1024);
+    assert_eq!(bw.total_out, 2048);
+    assert_eq!(bw.rate_in, 12.5);
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[0].1.contains(&("peer".to_owned(), "QmPeer".to_owned())));
+}
+
+#[test]
+fn endpoint_normalization() {
+    let mock = MockTransport::default();
+    mock.on("id", 200, r#"{"ID":"QmPeer","PublicKey":"","Addresses":null,"AgentVersion":"","ProtocolVersion":""}"#);
+
+    let expected = Url::parse("http://host:5001/api/v0/").unwrap();
+    for base in &["http://host:5001", "http://host:5001/", "http://host:5001/api/v0", "http://host:5001/api/v0/"] {
+        let client = ipfs::Client::with_transport(Url::parse(base).unwrap(), mock.clone());
+        assert_eq!(client.endpoint(), &expected);
+        client.id().unwrap();
+    }
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests.iter().all(|r| r.0 == "id"));
+}
+
+#[test]
+fn unusual_args() {
+    let path = "/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn/a file ünïcode?&#.txt";
+    let mock = MockTransport::default();
+    mock.on("resolve", 200, &format!(r#"{{"Path":"{}"}}"#, path));
+    mock.on("cat", 200, "contents");
+
+    let mut data = String::new();
+    mock.client().cat(path).unwrap().read_to_string(&mut data).unwrap();
+    assert_eq!(data, "contents");
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests[0].0, "resolve");
+    assert!(requests[0].1.contains(&("arg".to_owned(), path.to_owned())));
+    assert_eq!(requests[1].0, "cat");
+    assert!(requests[1].1.contains(&("arg".to_owned(), path.to_owned())));
+}
+
+#[test]
+fn query_encoding() {
+    let mock = MockTransport::default();
+    mock.on("resolve", 200, r#"{"Path":"/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#);
+
+    let path = "/ipns/example.com/a b#c&d=e+f%20ü";
+    mock.client().resolve(path, false).unwrap();
+
+    let url = &mock.urls.lock().unwrap()[0];
+    let query = url.query.as_ref().unwrap();
+    assert!(!query.contains(' ') && !query.contains('#') && !query.contains("&d="));
+    assert!(query.bytes().all(|b| b < 0x80));
+    let parsed = url.query_pairs().unwrap();
+    assert!(parsed.contains(&("arg".to_owned(), path.to_owned())));
+}
+
+#[test]
+fn name_pubsub() {
+    let mock = MockTransport::default();
+    mock.on("name/pubsub/state", 200, r#"{"Enabled":true}"#);
+    mock.on("name/pubsub/subs", 200, r#"{"Strings":["/ipns/QmPeer"]}"#);
+    mock.on("name/pubsub/cancel", 200, r#"{"Canceled":false}"#);
+    let client = mock.client();
+
+    assert!(client.name_pubsub_state().unwrap());
+    assert_eq!(client.name_pubsub_subs().unwrap(), vec!["/ipns/QmPeer"]);
+    assert!(!client.name_pubsub_cancel("/ipns/QmOther").unwrap());
+}
+
+#[test]
+fn log() {
+    use ipfs::log::LogLevel;
+
+    let mock = MockTransport::default();
+    mock.on("log/level", 200, r#"{"Message":"Changed log level of 'dht' to 'warning'\n"}"#);
+    mock.on("log/tail", 200, r#"{"event":"updatePeer","system":"dht"}
+
+{"event":"handleAddProvider","system":"dht"}
+"#);
+    let client = mock.client();
+
+    client.set_log_level("dht", LogLevel::Warn).unwrap();
+    let events: Vec<_> = client.log_tail().unwrap().map(|e| e.unwrap()).collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].find("event").and_then(|e| e.as_string()), Some("handleAddProvider"));
+
+    let requests = mock.requests.lock().unwrap();
+    let args: Vec<_> = requests[0].1.iter().filter(|a| a.0 == "arg").map(|a| &a.1[..]).collect();
+    assert_eq!(args, vec!["dht", "warning"]);
+}
+
+#[test]
+fn malformed_stream() {
+    let mock = MockTransport::default();
+    mock.on("log/tail", 200, r#"{"event":"first"}
+
+{"event":"second"}
+{"event":
+{"event":"after the error"}
+"#);
+
+    let mut events = mock.client().log_tail().unwrap();
+    assert_eq!(events.next().unwrap().unwrap().find("event").and_then(|e| e.as_string()), Some("first"));
+    assert_eq!(events.next().unwrap().unwrap().find("event").and_then(|e| e.as_string()), Some("second"));
+    assert!(events.next().unwrap().is_err());
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn subscription_decode_error() {
+    let mock = MockTransport::default();
+    mock.on("pubsub/sub", 200, r#"{}
+{"from":"AQID","data":"aGk=","seqno":"AQ==","topicIDs":["topic"]}
+{"from":"AQID","data":"not base64!","seqno":"Ag==","topicIDs":["topic"]}
+{"from":"AQID","data":"aGk=","seqno":"Aw==","topicIDs":["topic"]}
+"#);
+
+    let mut messages = mock.client().pubsub_subscribe("topic").unwrap();
+    let msg = messages.next().unwrap().unwrap();
+    assert_eq!(msg.data, b"hi");
+    assert_eq!(msg.seqno, vec![1]);
+    assert_eq!(messages.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    assert!(messages.next().is_none());
+}
+
+#[test]
+fn bootstrap() {
+    let peer = "/ip4/10.0.0.1/tcp/4001/ipfs/QmPeer";
+    let mock = MockTransport::default();
+    mock.on("bootstrap/add", 200, &format!(r#"{{"Peers":["{}"]}}"#, peer));
+    mock.on("bootstrap/list", 200, r#"{"Peers":null}"#);
+    let client = mock.client();
+
+    assert_eq!(client.bootstrap_add(peer).unwrap(), vec![peer]);
+    assert!(client.bootstrap_list().unwrap().is_empty());
+    client.bootstrap_add_default().unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[0].1.contains(&("arg".to_owned(), peer.to_owned())));
+    assert!(requests[2].1.contains(&("default".to_owned(), "true".to_owned())));
+}
+
+#[test]
+fn get_bare_hash() {
+    let hash = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, &format!(r#"{{"Hash":"{}","NumLinks":0,"DataSize":2,"CumulativeSize":4}}"#, hash));
+    mock.on("object/get", 200, "");
+
+    let obj = mock.client().get(hash).unwrap();
+    assert_eq!(obj.hash(), hash);
+    assert_eq!(obj.size(), 4);
+    assert!(obj.links.is_empty());
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[1].1.contains(&("arg".to_owned(), hash.to_owned())));
+}
+
+#[test]
+fn diag_cmds() {
+    use std::time::Duration;
+
+    let mock = MockTransport::default();
+    mock.on("diag/cmds", 200, r#"[
+        {"ID":1,"Command":"object/stat","StartTime":"2017-06-01T12:00:00Z","EndTime":"2017-06-01T12:00:01Z","Active":false},
+        {"ID":2,"Command":"diag/cmds","StartTime":"2017-06-01T12:00:02Z","Active":true}
+    ]"#);
+    mock.on("diag/cmds/set-time", 200, "");
+    let client = mock.client();
+
+    let cmds = client.diag_cmds(false).unwrap();
+    assert_eq!(cmds.len(), 2);
+    assert_eq!(cmds[0].command, "object/stat");
+    assert_eq!(cmds[0].end_time, Some("2017-06-01T12:00:01Z".to_owned()));
+    assert!(cmds[1].active);
+
+    client.diag_cmds_set_time(Duration::from_secs(60)).unwrap();
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[1].1.contains(&("arg".to_owned(), "60s0ns".to_owned())));
+}
+
+#[test]
+fn resolve_split() {
+    let hash = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    let mock = MockTransport::default();
+    mock.on("resolve", 200, &format!(r#"{{"Path":"/ipfs/{}/site"}}"#, hash));
+    mock.on("object/stat", 200, &format!(r#"{{"Hash":"{}","NumLinks":1,"DataSize":2,"CumulativeSize":100}}"#, hash));
+    let client = mock.client();
+
+    let (root, suffix) = client.resolve_split("/ipns/example.com/docs/readme.md").unwrap();
+    assert_eq!(root.hash(), hash);
+    assert_eq!(suffix, "site/docs/readme.md");
+
+    let (root, suffix) = client.resolve_split(hash).unwrap();
+    assert_eq!(root.hash(), hash);
+    assert_eq!(suffix, "");
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[0].1.contains(&("arg".to_owned(), "/ipns/example.com".to_owned())));
+}
+
+#[test]
+fn resolve_cache() {
+    use std::time::Duration;
+
+    let hash = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, &format!(r#"{{"Hash":"{}","NumLinks":0,"DataSize":2,"CumulativeSize":4}}"#, hash));
+    let mut client = mock.client();
+    let count = || mock.requests.lock().unwrap().len();
+
+    // Caching is off by default.
+    client.lookup("/ipns/example.com").unwrap();
+    client.lookup("/ipns/example.com").unwrap();
+    assert_eq!(count(), 2);
+
+    client.set_resolve_cache_ttl(Some(Duration::from_secs(60)));
+    assert_eq!(client.lookup("/ipns/example.com").unwrap().hash(), hash);
+    assert_eq!(client.lookup("/ipns/example.com").unwrap().hash(), hash);
+    client.lookup(hash).unwrap();
+    client.lookup(hash).unwrap();
+    assert_eq!(count(), 4);
+
+    client.clear_resolve_cache();
+    client.lookup("/ipns/example.com").unwrap();
+    assert_eq!(count(), 5);
+
+    // IPNS results expire, IPFS results don't.
+    client.set_resolve_cache_ttl(Some(Duration::from_millis(0)));
+    client.lookup(hash).unwrap();
+    client.lookup(hash).unwrap();
+    client.lookup("/ipns/other.example.com").unwrap();
+    client.lookup("/ipns/other.example.com").unwrap();
+    assert_eq!(count(), 8);
+}
+
+#[test]
+fn resolve_cache_bounded() {
+    use std::time::Duration;
+    use ipfs::object::StatOptions;
+
+    let hash = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, &format!(r#"{{"Hash":"{}","NumLinks":0,"DataSize":2,"CumulativeSize":4}}"#, hash));
+    let mut client = mock.client();
+    client.set_resolve_cache_ttl(Some(Duration::from_secs(60)));
+    let count = || mock.requests.lock().unwrap().len();
+
+    // Offline lookups don't share results with online ones.
+    client.lookup("/ipns/example.com").unwrap();
+    client.lookup_with("/ipns/example.com", &StatOptions { offline: true }).unwrap();
+    client.lookup_with("/ipns/example.com", &StatOptions { offline: true }).unwrap();
+    assert_eq!(count(), 2);
+
+    // Fill the cache, keeping example.com recently used.
+    for i in 0..1022 {
+        client.lookup(&format!("/ipns/{}.example.com", i)[..]).unwrap();
+    }
+    client.lookup("/ipns/example.com").unwrap();
+    assert_eq!(count(), 1024);
+
+    // One more evicts the least recently used result: the offline lookup.
+    client.lookup("/ipns/new.example.com").unwrap();
+    client.lookup("/ipns/example.com").unwrap();
+    client.lookup("/ipns/0.example.com").unwrap();
+    assert_eq!(count(), 1025);
+    client.lookup_with("/ipns/example.com", &StatOptions { offline: true }).unwrap();
+    assert_eq!(count(), 1026);
+}
+
+#[test]
+fn dht_get_put() {
+    let mock = MockTransport::default();
+    mock.on("version", 200, r#"{"Version":"0.5.0","Commit":"","Repo":"9","System":"amd64/linux"}"#);
+    mock.on("dht/get", 200, r#"{"ID":"","Type":6,"Responses":null,"Extra":""}
+{"ID":"QmPeer","Type":5,"Responses":null,"Extra":"b2xk"}
+{"ID":"QmOther","Type":5,"Responses":null,"Extra":"cmVjb3Jk"}
+"#);
+    mock.on("dht/put", 200, r#"{"ID":"","Type":3,"Responses":null,"Extra":"failed to find any peer in table"}
+"#);
+
+    let client = mock.client();
+    assert_eq!(client.dht_get("/ipns/QmPeer").unwrap(), b"record");
+
+    let err = client.dht_put("/ipns/QmPeer", b"record").unwrap_err();
+    assert_eq!(err.to_string(), "failed to find any peer in table");
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests[2].0, "dht/put");
+    assert!(requests[2].1.contains(&("arg".to_owned(), "/ipns/QmPeer".to_owned())));
+    assert_eq!(requests[2].2, b"record");
+}
+
+#[test]
+fn dht_get_old_node() {
+    // Before 0.5.0 the value isn't base64-encoded.
+    let mock = MockTransport::default();
+    mock.on("version", 200, r#"{"Version":"0.4.23","Commit":"","Repo":"7","System":"amd64/linux"}"#);
+    mock.on("dht/get", 200, r#"{"ID":"QmOther","Type":5,"Responses":null,"Extra":"record"}
+"#);
+    assert_eq!(mock.client().dht_get("/ipns/QmPeer").unwrap(), b"record");
+}
+
+#[test]
+fn repo_verify_and_version() {
+    let mock = MockTransport::default();
+    mock.on("repo/verify", 200, r#"{"Msg":"","Progress":1}
+{"Msg":"","Progress":2}
+{"Msg":"block QmBad was corrupt (block in storage has different hash than requested)","Progress":0}
+{"Msg":"","Progress":3}
+{"Msg":"verify complete, some blocks were corrupt","Progress":0}
+"#);
+    mock.on("repo/version", 200, r#"{"Version":"5"}"#);
+
+    let client = mock.client();
+    let verify = client.repo_verify().unwrap();
+    assert!(!verify.is_valid());
+    assert_eq!(verify.blocks_checked, 3);
+    assert_eq!(verify.problems.len(), 1);
+    assert!(verify.problems[0].contains("QmBad"));
+    assert_eq!(verify.message, "verify complete, some blocks were corrupt");
+    assert_eq!(client.repo_version().unwrap(), 5);
+
+    assert_eq!(mock.requests.lock().unwrap()[0].0, "repo/verify");
+}
+
+#[test]
+fn publish_with_options() {
+    use ipfs::name::{IpnsBase, PublishOptions};
+
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","NumLinks":0,"DataSize":2,"CumulativeSize":4}"#);
+    mock.on("name/publish", 200, r#"{"Name":"k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8","Value":"/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#);
+
+    let client = mock.client();
+    let r = client.lookup("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    let published = client.publish_with(&r, &PublishOptions {
+        ipns_base: Some(IpnsBase::Base36),
+        v1_compat: Some(false),
+        ..Default::default()
+    }).unwrap();
+    assert!(published.name.starts_with("k51"));
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests[1].0, "name/publish");
+    assert!(requests[1].1.contains(&("ipns-base".to_owned(), "base36".to_owned())));
+    assert!(requests[1].1.contains(&("v1compat".to_owned(), "false".to_owned())));
+    assert!(!requests[1].1.iter().any(|&(ref k, _)| k == "key"));
+}
+
+#[test]
+fn non_ipfs_error_body() {
+    let mock = MockTransport::default();
+    mock.on("object/stat", 502, "<html>\r\n<head><title>502 Bad Gateway</title></head>\r\n<body>\r\n<center><h1>502 Bad Gateway</h1></center>\r\n<hr><center>nginx</center>\r\n</body>\r\n</html>\r\n");
+    mock.on("repo/version", 500, r#"{"Message":"repo not initialized","Code":0}"#);
+
+    let client = mock.client();
+    let err = client.lookup("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    let message = err.to_string();
+    assert!(message.starts_with("HTTP status 502: <html>"), "{}", message);
+    assert!(!message.contains("parse"), "{}", message);
+
+    // Errors from the IPFS node itself are still reported as such.
+    assert_eq!(client.repo_version().unwrap_err().to_string(), "repo not initialized");
+}
+
+#[test]
+fn unpin_not_pinned() {
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","NumLinks":0,"DataSize":2,"CumulativeSize":4}"#);
+    mock.on("pin/rm", 500, r#"{"Message":"not pinned or pinned indirectly","Code":0}"#);
+
+    let client = mock.client();
+    let r = client.lookup("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    client.unpin(&r, true).unwrap();
+}
+
+#[test]
+fn reference_keeps_client() {
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","NumLinks":0,"DataSize":2,"CumulativeSize":4}"#);
+    mock.on("pin/add", 200, r#"{"Pins":["QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"]}"#);
+
+    let r = mock.client().lookup("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    r.pin(true).unwrap();
+    r.ensure_pinned(true).unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[1].0, "pin/add");
+    assert_eq!(requests[2].0, "pin/add");
+}
+
+#[test]
+fn retry_only_transient() {
+    use std::time::Duration;
+
+    let mock = MockTransport::default();
+    mock.on("object/stat", 500, r#"{"Message":"merkledag: not found","Code":0}"#);
+    mock.on("files/mkdir", 500, r#"{"Message":"internal error","Code":2}"#);
+
+    let mut client = mock.client();
+    client.set_retry_policy(Some(ipfs::RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+    }));
+    // Deterministic failures aren't retried.
+    assert!(client.lookup("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").is_err());
+    assert_eq!(mock.requests.lock().unwrap().len(), 1);
+    // Nor are requests that change something.
+    assert!(client.files_mkdir("/retry", false).is_err());
+    assert_eq!(mock.requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn links_streaming_truncated() {
+    let mock = MockTransport::default();
+    // A link claiming to be 127 bytes long followed by 3 bytes.
+    mock.on("object/get", 200, "\x12\x7fabc");
+
+    let mut links = mock.client().get_links_streaming("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    assert_eq!(links.next().unwrap().unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    assert!(links.next().is_none());
+}
+
+#[test]
+fn version_cached() {
+    use std::time::Duration;
+
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","NumLinks":0,"DataSize":2,"CumulativeSize":4}"#);
+    mock.on("version", 200, r#"{"Version":"0.4.13","Commit":"","Repo":"6","System":"amd64/linux"}"#);
+    mock.on("name/publish", 200, r#"{"Name":"QmKey","Value":"/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#);
+
+    let client = mock.client();
+    let r = client.lookup("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    client.publish_with_key(&r, "mykey", Duration::from_secs(60)).unwrap();
+    client.clone().publish_with_key(&r, "mykey", Duration::from_secs(60)).unwrap();
+
+    let requests = mock.requests.lock().unwrap();
+    assert_eq!(requests.iter().filter(|r| r.0 == "version").count(), 1);
+    assert_eq!(requests.iter().filter(|r| r.0 == "name/publish").count(), 2);
+}
+
+#[test]
+fn publish_with_key_old_node() {
+    use ipfs::name::PublishOptions;
+
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","NumLinks":0,"DataSize":2,"CumulativeSize":4}"#);
+    mock.on("version", 200, r#"{"Version":"0.4.10","Commit":"","Repo":"5","System":"amd64/linux"}"#);
+
+    let client = mock.client();
+    let r = client.lookup("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    assert!(client.publish_with(&r, &PublishOptions {
+        key: Some("mykey"),
+        ..Default::default()
+    }).is_err());
+    assert!(!mock.requests.lock().unwrap().iter().any(|r| r.0 == "name/publish"));
+}
+
+#[test]
+fn put_block_options() {
+    use ipfs::block::{BlockFormat, HashFunction, PutBlockOptions};
+
+    let mock = MockTransport::default();
+    mock.on("block/put", 200, r#"{"Key":"bafkr4ifk6ikmvvimvtf2kofllsle6dwyrn7phghqjvljlazjnvl7xwv27u","Size":9}"#);
+
+    let r = mock.client().put_block_with(b"raw block", BlockFormat::Raw, &PutBlockOptions {
+        hash: HashFunction::Blake2b256,
+        cid_version: Some(1),
+    }).unwrap();
+    assert_eq!(r.hash(), "bafkr4ifk6ikmvvimvtf2kofllsle6dwyrn7phghqjvljlazjnvl7xwv27u");
+    assert_eq!(r.size(), 9);
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[0].1.contains(&("mhtype".to_owned(), "blake2b-256".to_owned())));
+    assert!(requests[0].1.contains(&("cid-version".to_owned(), "1".to_owned())));
+    assert_eq!(requests[0].2, b"raw block");
+}
+
+#[test]
+fn refs_stream() {
+    use ipfs::refs::RefsOptions;
+
+    let mock = MockTransport::default();
+    mock.on("refs", 200, r#"{"Ref":"QmA","Err":""}
+{"Ref":"QmB","Err":""}
+"#);
+    let refs = mock.client().refs("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn", RefsOptions {
+        max_depth: Some(2),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(refs, vec!["QmA", "QmB"]);
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[0].1.contains(&("encoding".to_owned(), "json".to_owned())));
+    assert!(requests[0].1.contains(&("max-depth".to_owned(), "2".to_owned())));
+}
+
+#[test]
+fn refs_error() {
+    let mock = MockTransport::default();
+    mock.on("refs", 200, r#"{"Ref":"QmA","Err":""}
+{"Ref":"","Err":"merkledag: not found"}
+{"Ref":"QmB","Err":""}
+"#);
+    let err = mock.client().refs("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn", Default::default()).unwrap_err();
+    assert_eq!(err.to_string(), "merkledag: not found");
+}
+
+#[test]
+fn gc() {
+    let mock = MockTransport::default();
+    mock.on("repo/gc", 200, r#"{"Key":{"/":"QmA"}}
+{"Key":{"/":"QmB"}}
+"#);
+    assert_eq!(mock.client().gc().unwrap(), vec!["QmA", "QmB"]);
+
+    let failing = MockTransport::default();
+    failing.on("repo/gc", 200, r#"{"Key":{"/":"QmA"}}
+{"Error":"could not remove QmB"}
+"#);
+    assert_eq!(failing.client().gc().unwrap_err().to_string(), "could not remove QmB");
+}
+
+#[test]
+fn find_peer_stops_early() {
+    let mock = MockTransport::default();
+    // Anything after the peer is found isn't read.
+    mock.on("dht/findpeer", 200, r#"{"ID":"","Type":1,"Responses":null,"Extra":""}
+{"ID":"","Type":2,"Responses":[{"ID":"QmPeer","Addrs":["/ip4/1.2.3.4/tcp/4001"]}],"Extra":""}
+{"ID":
+"#);
+    assert_eq!(mock.client().find_peer("QmPeer").unwrap(), vec!["/ip4/1.2.3.4/tcp/4001"]);
+}
+
+#[test]
+fn object_put_old_node() {
+    use ipfs::object::Object;
+
+    let hash = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+    let mock = MockTransport::default();
+    mock.on("version", 200, r#"{"Version":"0.4.2","Commit":"","Repo":"3","System":"amd64/linux"}"#);
+    mock.on("object/put", 200, &format!(r#"{{"Hash":"{}","Links":[]}}"#, hash));
+    mock.on("object/stat", 200, &format!(r#"{{"Hash":"{}","NumLinks":0,"DataSize":5,"CumulativeSize":7}}"#, hash));
+
+    let client = mock.client();
+    client.commit(Object {
+        data: b"hello".to_vec(),
+        links: vec![],
+    }).unwrap();
+    client.put_object_json(b"hello".to_vec(), vec![]).unwrap();
+    assert!(client.put_object_json(vec![0xff], vec![]).is_err());
+
+    let requests = mock.requests.lock().unwrap();
+    let puts: Vec<_> = requests.iter().filter(|r| r.0 == "object/put").collect();
+    assert_eq!(puts.len(), 2);
+    assert!(puts[0].1.contains(&("arg".to_owned(), "protobuf".to_owned())));
+    assert!(puts[1].1.contains(&("arg".to_owned(), "json".to_owned())));
+    assert!(!puts.iter().any(|r| r.1.iter().any(|a| a.0 == "inputenc" || a.0 == "datafieldenc")));
+}
+
+#[test]
+fn children_error() {
+    use ipfs::object::{Link, Object, Reference};
+
+    let hashes = ["QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn",
+                  "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n",
+                  "QmTnaGEpw4totXN7rhv2jPMXKfL8s65PhhCKL5pwtJfRxn"];
+    let mock = MockTransport::default();
+    mock.on("version", 200, r#"{"Version":"0.4.13","Commit":"","Repo":"6","System":"amd64/linux"}"#);
+    mock.on("object/put", 200, r#"{"Hash":"QmParent","Links":[]}"#);
+    // The middle child is missing.
+    mock.once("object/get", 200, "");
+    mock.once("object/get", 500, r#"{"Message":"merkledag: not found","Code":0}"#);
+    mock.on("object/get", 200, "");
+
+    let parent = mock.client().commit(Object {
+        data: vec![],
+        links: hashes.iter().map(|h| Link {
+            name: String::new(),
+            object: Reference::new(h.to_string(), 0),
+        }).collect(),
+    }).unwrap();
+    let children: Vec<_> = parent.children().collect();
+    assert_eq!(children.len(), 3);
+    assert_eq!(children[0].as_ref().unwrap().hash(), hashes[0]);
+    assert_eq!(children[1].as_ref().unwrap_err().to_string(), "merkledag: not found (code 0)");
+    assert_eq!(children[2].as_ref().unwrap().hash(), hashes[2]);
+
+    let requests = mock.requests.lock().unwrap();
+    let gets: Vec<_> = requests.iter().filter(|r| r.0 == "object/get").collect();
+    for (get, hash) in gets.iter().zip(hashes.iter()) {
+        assert!(get.1.contains(&("arg".to_owned(), hash.to_string())));
+    }
+}
+
+#[test]
+fn commit_verified_hash_mismatch() {
+    use ipfs::object::Object;
+
+    let object = Object {
+        data: b"hello".to_vec(),
+        links: vec![],
+    };
+    let mock = MockTransport::default();
+    mock.on("version", 200, r#"{"Version":"0.4.13","Commit":"","Repo":"6","System":"amd64/linux"}"#);
+    mock.on("object/put", 200, r#"{"Hash":"QmTnaGEpw4totXN7rhv2jPMXKfL8s65PhhCKL5pwtJfRxn","Links":[]}"#);
+    let committed = mock.client().commit_verified(object.clone()).unwrap();
+    assert_eq!(committed.hash(), "QmTnaGEpw4totXN7rhv2jPMXKfL8s65PhhCKL5pwtJfRxn");
+
+    // A node returning the hash of some other object.
+    let mock = MockTransport::default();
+    mock.on("version", 200, r#"{"Version":"0.4.13","Commit":"","Repo":"6","System":"amd64/linux"}"#);
+    mock.on("object/put", 200, r#"{"Hash":"QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n","Links":[]}"#);
+    let err = mock.client().commit_verified(object.clone()).unwrap_err();
+    assert_eq!(err.error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "hash mismatch: the IPFS node returned QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n");
+    assert_eq!(err.object, object);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn get_async() {
+    use futures::Future;
+
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","NumLinks":0,"DataSize":0,"CumulativeSize":0}"#);
+    mock.on("object/get", 200, "");
+
+    let obj = mock.client().get_async("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").wait().unwrap();
+    assert_eq!(obj.hash(), "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn");
+    assert!(obj.data.is_empty());
+    assert_eq!(mock.requests.lock().unwrap().last().unwrap().0, "object/get");
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn stat_async() {
+    use futures::Future;
+
+    let mock = MockTransport::default();
+    mock.on("object/stat", 200, r#"{"Hash":"QmTest","NumLinks":2,"DataSize":3,"CumulativeSize":100}"#);
+
+    let stat = mock.client().stat_async("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").wait().unwrap();
+    assert_eq!(stat.hash, "QmTest");
+    assert_eq!(stat.cumulative_size, 100);
+
+    // Errors come through the future too.
+    let mock = MockTransport::default();
+    assert!(mock.client().stat_async("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").wait().is_err());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn resolve_async() {
+    use futures::Future;
+
+    let mock = MockTransport::default();
+    mock.on("resolve", 200, r#"{"Path":"/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#);
+
+    let path = mock.client().resolve_async("/ipns/example.com", true).wait().unwrap();
+    assert_eq!(path, "/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn");
+
+    let requests = mock.requests.lock().unwrap();
+    assert!(requests[0].1.contains(&("recursive".to_owned(), "true".to_owned())));
+    assert!(requests[0].1.contains(&("arg".to_owned(), "/ipns/example.com".to_owned())));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn add_async() {
+    use futures::Future;
+
+    let mock = MockTransport::default();
+    mock.on("add", 200, r#"{"Name":"QmFile","Hash":"QmFile","Size":"19"}
+"#);
+
+    let r = mock.client().add_async(b"hello world".to_vec()).wait().unwrap();
+    assert_eq!(r.hash(), "QmFile");
+    assert_eq!(r.size(), 19);
+    assert_eq!(mock.requests.lock().unwrap()[0].2, b"hello world");
+}
+
+#[test]
+fn open_file_seek_overflow() {
+    use std::io::{Seek, SeekFrom};
+
+    let mock = MockTransport::default();
+    mock.on("resolve", 200, r#"{"Path":"/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#);
+    mock.on("files/stat", 200, r#"{"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","Size":1000,"CumulativeSize":1011,"Blocks":0,"Type":"file"}"#);
+
+    let mut file = mock.client().open_file("/ipns/example.com").unwrap();
+    assert_eq!(file.seek(SeekFrom::Start(u64::max_value())).unwrap(), u64::max_value());
+    assert_eq!(file.seek(SeekFrom::Current(1)).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(file.seek(SeekFrom::Current(i64::min_value())).unwrap(), u64::max_value() - (1 << 63));
+    assert_eq!(file.seek(SeekFrom::Current(i64::min_value())).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(file.seek(SeekFrom::End(i64::max_value())).unwrap(), 1000 + i64::max_value() as u64);
+    assert_eq!(file.seek(SeekFrom::End(-1001)).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    // Failed seeks don't move the file.
+    assert_eq!(file.seek(SeekFrom::Current(0)).unwrap(), 1000 + i64::max_value() as u64);
+}
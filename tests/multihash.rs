@@ -0,0 +1,43 @@
+extern crate ipfs_api as ipfs;
+
+use ipfs::multihash::{self, Multihash, CidError};
+
+#[test]
+fn sha2_256() {
+    let hash = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    let mh = multihash::decode(hash).unwrap();
+    assert_eq!(mh.code, multihash::SHA2_256);
+    assert_eq!(mh.digest.len(), 32);
+    assert_eq!(multihash::encode(&mh), hash);
+    assert_eq!(Multihash::from_bytes(&mh.to_bytes()), Ok(mh));
+}
+
+#[test]
+fn cid_v1() {
+    let v0 = multihash::decode("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn").unwrap();
+    let v1 = multihash::decode("bafybeiczsscdsbs7ffqz55asqdf3smv6klcw3gofszvwlyarci47bgf354").unwrap();
+    assert_eq!(v0, v1);
+}
+
+#[test]
+fn invalid_length() {
+    // sha2-256 claiming a 32 byte digest but carrying only 4 bytes.
+    assert_eq!(Multihash::from_bytes(&[0x12, 32, 1, 2, 3, 4]), Err(CidError::InvalidLength));
+    assert_eq!(Multihash::from_bytes(&[0x12, 0]), Err(CidError::InvalidLength));
+    assert!(multihash::decode("QmNotAHash").is_err());
+}
+
+#[test]
+fn convert_cid() {
+    let v0 = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    let v1 = "bafybeiczsscdsbs7ffqz55asqdf3smv6klcw3gofszvwlyarci47bgf354";
+    assert_eq!(multihash::convert_cid(v0, 1).unwrap(), v1);
+    assert_eq!(multihash::convert_cid(v1, 0).unwrap(), v0);
+    assert_eq!(multihash::convert_cid(v0, 0).unwrap(), v0);
+    assert_eq!(multihash::convert_cid(v1, 1).unwrap(), v1);
+
+    // Raw leaves can't be version 0 CIDs.
+    let raw = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+    assert!(multihash::convert_cid(raw, 0).is_err());
+    assert!(multihash::convert_cid(v0, 2).is_err());
+}
@@ -0,0 +1,50 @@
+extern crate ipfs_api as ipfs;
+
+use std::io;
+
+use ipfs::path::{IpfsPath, Namespace};
+
+const HASH: &'static str = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+
+#[test]
+fn bare_hash() {
+    let path = IpfsPath::parse(HASH).unwrap();
+    assert_eq!(path.namespace(), Namespace::Ipfs);
+    assert_eq!(path.root(), HASH);
+    assert_eq!(path.remainder(), None);
+    assert_eq!(path.as_str(), format!("/ipfs/{}", HASH));
+}
+
+#[test]
+fn ipfs_path() {
+    let path: IpfsPath = format!("/ipfs/{}/a/b/", HASH).parse().unwrap();
+    assert_eq!(path.root(), HASH);
+    assert_eq!(path.remainder(), Some("a/b"));
+    assert_eq!(path.to_string(), format!("/ipfs/{}/a/b", HASH));
+    assert_eq!(IpfsPath::parse(&format!("{}/a/b", HASH)).unwrap(), path);
+}
+
+#[test]
+fn ipns_path() {
+    let path = IpfsPath::parse("/ipns/ipfs.io/docs").unwrap();
+    assert_eq!(path.namespace(), Namespace::Ipns);
+    assert_eq!(path.root(), "ipfs.io");
+    assert_eq!(path.remainder(), Some("docs"));
+}
+
+#[test]
+fn other_multibase() {
+    // The empty UnixFS directory as a base36 CID.
+    let base36 = "k2jmtxtlhjl3fhmgndf92e48by79ryjuvqp3y2qgehpao6v3lurvnmcv";
+    let path = IpfsPath::parse(&format!("/ipfs/{}/a", base36)).unwrap();
+    assert_eq!(path.root(), base36);
+    assert_eq!(path.remainder(), Some("a"));
+    assert!(IpfsPath::parse("k2k4r8jl!").is_err());
+}
+
+#[test]
+fn invalid() {
+    for path in &["", "/", "/ipfs/", "/ipns/", "/ipfs/not-a-hash", "not-a-hash", "/foo/bar"] {
+        assert_eq!(IpfsPath::parse(path).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}